@@ -0,0 +1,131 @@
+use crate::config::asconfig_json::Define;
+use crate::config::mod_conf::ModConf;
+use serde_json::Value;
+use std::path::PathBuf;
+
+pub const MODULE_PREFIX: &str = "_build_info_";
+
+// Values baked into the compiled mod, so they don't have to be duplicated
+// from mod.json in the sources
+pub struct BuildInfo {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub commit: Option<String>,
+    pub debug: bool,
+}
+
+fn git_commit(mod_path: &PathBuf) -> Option<String> {
+    let repository = git2::Repository::discover(mod_path).ok()?;
+    let commit = repository.head().ok()?.peel_to_commit().ok()?;
+
+    Some(commit.id().to_string())
+}
+
+// A JSON string is also a valid Python and AS3 string literal
+fn quote(value: &str) -> String {
+    Value::String(value.to_string()).to_string()
+}
+
+impl BuildInfo {
+    pub fn new(mod_conf: &ModConf, mod_path: &PathBuf, debug: bool) -> Self {
+        Self {
+            id: mod_conf.package_name.clone(),
+            name: mod_conf.name.clone(),
+            version: mod_conf.version.clone(),
+            commit: git_commit(mod_path),
+            debug,
+        }
+    }
+
+    // Every mod lands in the same gui/mods package, so the module name carries
+    // the mod id: com.example.mod -> gui.mods._build_info_com_example_mod
+    pub fn module_name(&self) -> String {
+        let id = self
+            .id
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                | true => c.to_ascii_lowercase(),
+                | false => '_',
+            })
+            .collect::<String>();
+
+        format!("{MODULE_PREFIX}{id}")
+    }
+
+    pub fn python_module(&self) -> String {
+        let commit = match &self.commit {
+            | Some(commit) => quote(commit),
+            | None => "None".to_string(),
+        };
+        let debug = if self.debug { "True" } else { "False" };
+
+        format!(
+            "# -*- coding: utf-8 -*-\n\
+             # Generated by wg-mod, do not edit\n\
+             \n\
+             MOD_ID = {}\n\
+             NAME = {}\n\
+             VERSION = {}\n\
+             COMMIT = {}\n\
+             DEBUG = {}\n",
+            quote(&self.id),
+            quote(&self.name),
+            quote(&self.version),
+            commit,
+            debug
+        )
+    }
+
+    pub fn flash_defines(&self) -> Vec<Define> {
+        let define = |name: &str, value: Value| Define {
+            name: format!("CONFIG::{name}"),
+            value,
+        };
+
+        vec![
+            define("id", Value::String(quote(&self.id))),
+            define("version", Value::String(quote(&self.version))),
+            define(
+                "commit",
+                Value::String(quote(self.commit.as_deref().unwrap_or(""))),
+            ),
+            define("debug", Value::Bool(self.debug)),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_build_info() {
+        let build_info = BuildInfo {
+            id: "com.example.my-mod".to_string(),
+            name: "My \"best\" mod".to_string(),
+            version: "1.2.0".to_string(),
+            commit: None,
+            debug: true,
+        };
+
+        assert_eq!(
+            build_info.python_module(),
+            "# -*- coding: utf-8 -*-\n\
+             # Generated by wg-mod, do not edit\n\
+             \n\
+             MOD_ID = \"com.example.my-mod\"\n\
+             NAME = \"My \\\"best\\\" mod\"\n\
+             VERSION = \"1.2.0\"\n\
+             COMMIT = None\n\
+             DEBUG = True\n"
+        );
+
+        assert_eq!(build_info.module_name(), "_build_info_com_example_my_mod");
+
+        let defines = build_info.flash_defines();
+        assert_eq!(defines[1].name, "CONFIG::version");
+        assert_eq!(defines[1].value, Value::String("\"1.2.0\"".to_string()));
+        assert_eq!(defines[3].value, Value::Bool(true));
+    }
+}
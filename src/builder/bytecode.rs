@@ -0,0 +1,182 @@
+use crate::builder::diagnostic::{Diagnostic, Severity};
+use glob::glob;
+use std::fs::{metadata, read};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to read {0}\n{1}")]
+    ReadError(PathBuf, io::Error),
+
+    #[error("File selection by pattern failed")]
+    GlobError(#[from] glob::GlobError),
+
+    #[error("Invalid pattern given")]
+    PatternError(#[from] glob::PatternError),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+// CPython 2.7 writes 62211 as a little endian u16 followed by "\r\n"
+pub const PYTHON_27_MAGIC: u16 = 62211;
+const HEADER_SIZE: usize = 8;
+// Marshal type of the module code object right after the header
+const TYPE_CODE: u8 = b'c';
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn source_timestamp(source: &Path) -> Result<u32> {
+    let modified = metadata(source)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| Error::ReadError(source.to_path_buf(), e))?;
+    let seconds = modified
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    Ok(seconds as u32)
+}
+
+fn check_header(bytecode: &[u8], timestamp: u32) -> Option<String> {
+    if bytecode.len() <= HEADER_SIZE {
+        return Some(format!(
+            "Truncated bytecode ({} bytes), the header is {HEADER_SIZE} bytes",
+            bytecode.len()
+        ));
+    }
+
+    let magic = u16::from_le_bytes([bytecode[0], bytecode[1]]);
+    if magic != PYTHON_27_MAGIC || bytecode[2..4] != *b"\r\n" {
+        return Some(format!(
+            "Bad magic number {magic}, expected {PYTHON_27_MAGIC} (CPython 2.7), \
+             check the Python version of the wg-mod conda environment"
+        ));
+    }
+
+    let compiled_timestamp = read_u32(&bytecode[4..HEADER_SIZE]);
+    if compiled_timestamp != timestamp {
+        return Some(format!(
+            "Bytecode timestamp {compiled_timestamp} doesn't match its source \
+             ({timestamp})"
+        ));
+    }
+
+    if bytecode[HEADER_SIZE] & 0x7f != TYPE_CODE {
+        return Some("Bytecode doesn't contain a code object".to_string());
+    }
+
+    None
+}
+
+// Every source must have been compiled next to itself by a 2.7 interpreter,
+// Python 3 would write into __pycache__ with another magic number
+pub fn check_bytecode(
+    directory: &Path, display_root: &Path, extension: &str,
+) -> Result<Vec<Diagnostic>> {
+    let mut diagnostics = vec![];
+    let diagnostic = |file: &Path, message: String| Diagnostic {
+        file: display_root.join(file.strip_prefix(directory).unwrap_or(file)),
+        line: None,
+        column: None,
+        severity: Severity::Error,
+        message,
+        snippet: vec![],
+    };
+
+    let pattern = directory.join("**").join("*.py");
+    for source in glob(&pattern.to_string_lossy())? {
+        let source = source?;
+        let compiled = source.with_extension(extension);
+        if !compiled.exists() {
+            diagnostics.push(diagnostic(
+                &source,
+                format!("No .{extension} was produced for this source"),
+            ));
+            continue;
+        }
+
+        let bytecode = read(&compiled)
+            .map_err(|e| Error::ReadError(compiled.clone(), e))?;
+        if let Some(message) =
+            check_header(&bytecode, source_timestamp(&source)?)
+        {
+            diagnostics.push(diagnostic(&compiled, message));
+        }
+    }
+
+    let pattern = directory.join("**").join("__pycache__");
+    for cache in glob(&pattern.to_string_lossy())? {
+        diagnostics.push(diagnostic(
+            &cache?,
+            "Python 3 bytecode cache found, the game runs CPython 2.7"
+                .to_string(),
+        ));
+    }
+
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, write, File};
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn write_source(path: &Path, timestamp: u64) {
+        write(path, "x = 1\n").unwrap();
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(timestamp))
+            .unwrap();
+    }
+
+    fn bytecode(magic: u16, timestamp: u32) -> Vec<u8> {
+        let mut bytes = magic.to_le_bytes().to_vec();
+        bytes.extend(b"\r\n");
+        bytes.extend(timestamp.to_le_bytes());
+        bytes.extend(b"c\x00\x00\x00\x00");
+        bytes
+    }
+
+    #[test]
+    fn check_python_27_bytecode() {
+        let tmp_dir = tempdir().unwrap();
+        let directory = tmp_dir.path().join("scripts");
+        create_dir_all(directory.join("__pycache__")).unwrap();
+
+        write_source(&directory.join("valid.py"), 1700000000);
+        write(directory.join("valid.pyc"), bytecode(62211, 1700000000))
+            .unwrap();
+        write_source(&directory.join("python3.py"), 1700000000);
+        write(directory.join("python3.pyc"), bytecode(3413, 1700000000))
+            .unwrap();
+        write_source(&directory.join("stale.py"), 1700000000);
+        write(directory.join("stale.pyc"), bytecode(62211, 1600000000))
+            .unwrap();
+        write_source(&directory.join("missing.py"), 1700000000);
+
+        let diagnostics =
+            check_bytecode(&directory, Path::new("scripts"), "pyc").unwrap();
+        let messages = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            messages,
+            vec![
+                "scripts/missing.py: error: No .pyc was produced for this source",
+                "scripts/python3.pyc: error: Bad magic number 3413, expected 62211 (CPython 2.7), check the Python version of the wg-mod conda environment",
+                "scripts/stale.pyc: error: Bytecode timestamp 1600000000 doesn't match its source (1700000000)",
+                "scripts/__pycache__: error: Python 3 bytecode cache found, the game runs CPython 2.7",
+            ]
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{create_dir_all, File};
+use std::path::PathBuf;
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to write the build cache\n{0}")]
+    WriteError(#[from] io::Error),
+
+    #[error("Unable to serialize the build cache\n{0}")]
+    SerializeError(#[from] serde_json::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheContent {
+    #[serde(rename = "stages")]
+    stages: BTreeMap<String, String>,
+}
+
+pub struct BuildCache {
+    cache_file: PathBuf,
+    content: CacheContent,
+}
+
+impl BuildCache {
+    pub fn load(target_path: &PathBuf) -> Self {
+        let cache_file = target_path.join("build-cache.json");
+        let content = File::open(&cache_file)
+            .ok()
+            .and_then(|file| serde_json::from_reader(file).ok())
+            .unwrap_or_default();
+
+        Self {
+            cache_file,
+            content,
+        }
+    }
+
+    pub fn is_fresh(&self, stage: &str, fingerprint: &str) -> bool {
+        self.content
+            .stages
+            .get(stage)
+            .is_some_and(|cached| cached == fingerprint)
+    }
+
+    pub fn update(&mut self, stage: &str, fingerprint: String) {
+        self.content.stages.insert(stage.to_string(), fingerprint);
+    }
+
+    pub fn invalidate(&mut self, stage: &str) {
+        self.content.stages.remove(stage);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.cache_file.parent() {
+            create_dir_all(parent)?;
+        }
+
+        let file = File::create(&self.cache_file)?;
+        serde_json::to_writer_pretty(file, &self.content)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn build_cache_persists_fingerprints() {
+        let tmp_dir = tempdir().unwrap();
+        let target_path = tmp_dir.path().join("target");
+
+        let mut cache = BuildCache::load(&target_path);
+        assert!(!cache.is_fresh("python", "abc"));
+
+        cache.update("python", "abc".to_string());
+        cache.save().unwrap();
+
+        let mut cache = BuildCache::load(&target_path);
+        assert!(cache.is_fresh("python", "abc"));
+        assert!(!cache.is_fresh("python", "def"));
+
+        cache.invalidate("python");
+        assert!(!cache.is_fresh("python", "abc"));
+
+        tmp_dir.close().unwrap();
+    }
+}
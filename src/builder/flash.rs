@@ -1,3 +1,5 @@
+use crate::builder::diagnostic::{Diagnostic, Diagnostics, Severity};
+use crate::builder::report;
 use crate::config;
 use crate::config::Configs;
 use crate::sdk::asconfigc;
@@ -5,9 +7,13 @@ use crate::sdk::asconfigc::ASConfigc;
 use crate::utils::copy_directory;
 use crate::utils::tmp_dir::TempDirError;
 use glob::{GlobError, PatternError};
+use regex::Regex;
 use std::fs::create_dir_all;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -25,28 +31,153 @@ pub enum Error {
     GlobUnwrapError(#[from] GlobError),
     #[error("Failed to creta glob: {0}")]
     GlobCreationError(#[from] PatternError),
+    #[error("ActionScript compilation failed\n{0}")]
+    CompileError(Diagnostics),
 }
 
 pub struct FlashBuilder {
-    asconfigc: ASConfigc,
+    configs: Arc<Configs>,
 }
 
 impl FlashBuilder {
-    pub fn new() -> Result<Self, Error> {
-        let config = Configs::load().map_err(|e| Error::AsConfigcError(e))?;
+    pub fn new(configs: Arc<Configs>) -> Self {
+        Self { configs }
+    }
 
-        Ok(Self {
-            asconfigc: config.asconfigc,
-        })
+    fn asconfigc(&self) -> &ASConfigc {
+        &self.configs.asconfigc
     }
 
     pub fn build(
-        &self, source: &PathBuf, destination: &PathBuf,
+        &self, config: &PathBuf, mod_path: &PathBuf, destination: &PathBuf,
+        cancel: &AtomicBool,
     ) -> Result<(), Error> {
-        self.asconfigc.build(source)?;
+        let as3_sdk_path = self.configs.as3.get_as3_path();
+        let output = match self.asconfigc().build(&as3_sdk_path, config, cancel)
+        {
+            | Ok(output) => output,
+            | Err(asconfigc::Error::BadExitStatus(output)) => {
+                let diagnostics = parse_output(&output, mod_path);
+                report::diagnostics("flash", &diagnostics);
+                if diagnostics.has_errors() {
+                    return Err(Error::CompileError(diagnostics));
+                }
+                return Err(asconfigc::Error::BadExitStatus(output).into());
+            },
+            | Err(e) => return Err(e.into()),
+        };
+
+        report::diagnostics("flash", &parse_output(&output, mod_path));
 
         create_dir_all(destination)?;
 
         Ok(())
     }
 }
+
+// Relative to the mod folder, targets may compile sources outside of ui/src
+fn relative_source_path(file: &str, mod_path: &Path) -> PathBuf {
+    let file = PathBuf::from(file);
+    let mod_path = mod_path.canonicalize().unwrap_or(mod_path.to_path_buf());
+    let file = file.canonicalize().unwrap_or(file);
+
+    match file.strip_prefix(&mod_path) {
+        | Ok(relative) => relative.to_path_buf(),
+        | Err(_) => file,
+    }
+}
+
+fn parse_output(output: &Output, mod_path: &Path) -> Diagnostics {
+    let content = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    parse_compiler_output(&content, mod_path)
+}
+
+// mxmlc reports problems as
+// <path>(<line>): col: <column> Error: <message>
+// followed by the faulty code line and a caret
+fn parse_compiler_output(content: &str, mod_path: &Path) -> Diagnostics {
+    let located = Regex::new(
+        r"^(.+?)\((\d+)\):\s*col:\s*(\d+)\s+(Error|Warning):\s*(.*)$",
+    )
+    .unwrap();
+
+    let mut diagnostics: Vec<Diagnostic> = vec![];
+    let mut in_snippet = false;
+
+    for line in content.lines() {
+        if let Some(captures) = located.captures(line.trim()) {
+            diagnostics.push(Diagnostic {
+                file: relative_source_path(&captures[1], mod_path),
+                line: captures[2].parse().ok(),
+                column: captures[3].parse().ok(),
+                severity: match &captures[4] {
+                    | "Warning" => Severity::Warning,
+                    | _ => Severity::Error,
+                },
+                message: captures[5].to_string(),
+                snippet: vec![],
+            });
+            in_snippet = true;
+            continue;
+        }
+
+        if !in_snippet || line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(diagnostic) = diagnostics.last_mut() {
+            diagnostic.snippet.push(line.trim_end().to_string());
+            // The caret closes the snippet
+            in_snippet = !line.trim().starts_with('^');
+        }
+    }
+
+    Diagnostics(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mxmlc_diagnostics() {
+        let output = "Loading configuration: /sdk/frameworks/flex-config.xml
+
+/mod/ui/src/my/mod/Main.as(12): col: 19 Error: Access of undefined property foo.
+
+            trace(foo);
+                  ^
+
+/mod/ui/hangar/src/my/mod/View.as(3): col: 5 Warning: Variable 'a' has no type declaration.
+
+    var a = 1;
+    ^
+
+/mod/ui/src/my/mod/Main.as(20): col: 1 Error: Syntax error: expecting rightbrace before end of program.
+
+";
+
+        let diagnostics = parse_compiler_output(output, Path::new("/mod"));
+
+        assert!(diagnostics.has_errors());
+        assert_eq!(diagnostics.0.len(), 3);
+        assert_eq!(diagnostics.0[1].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics.to_string(),
+            "ui/src/my/mod/Main.as:12:19: error: Access of undefined property foo.
+                trace(foo);
+                      ^
+ui/src/my/mod/Main.as:20:1: error: Syntax error: expecting rightbrace before end of program.
+
+ui/hangar/src/my/mod/View.as:3:5: warning: Variable 'a' has no type declaration.
+        var a = 1;
+        ^
+2 error(s) found"
+        );
+    }
+}
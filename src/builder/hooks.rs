@@ -0,0 +1,113 @@
+use crate::builder::report;
+use crate::utils::Env;
+use std::path::PathBuf;
+use std::process::Command;
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to run the {0} hook \"{1}\"\n{2}")]
+    ExecutionError(String, String, io::Error),
+
+    #[error("The {0} hook \"{1}\" failed\n{2}")]
+    HookFailedError(String, String, String),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub struct HookRunner {
+    mod_path: PathBuf,
+    env: Vec<Env>,
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+impl HookRunner {
+    pub fn new(mod_path: PathBuf, env: Vec<Env>) -> Self {
+        Self { mod_path, env }
+    }
+
+    // Hooks run from the mod folder, in declaration order
+    pub fn run(&self, stage: &str, commands: &[String]) -> Result<()> {
+        for command in commands {
+            report::message(&format!("Run {stage} hook: {command}"));
+
+            let mut shell = shell_command(command);
+            shell.current_dir(&self.mod_path);
+            self.env.iter().for_each(|env| {
+                shell.env(&env.key, &env.value);
+            });
+
+            let output = shell.output().map_err(|e| {
+                Error::ExecutionError(stage.to_string(), command.clone(), e)
+            })?;
+            let content = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+
+            if !output.status.success() {
+                return Err(Error::HookFailedError(
+                    stage.to_string(),
+                    command.clone(),
+                    content.trim_end().to_string(),
+                ));
+            }
+            if !content.trim().is_empty() {
+                report::message(content.trim_end());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+    use tempfile::tempdir;
+
+    #[test]
+    fn run_hooks_with_build_environment() {
+        let tmp_dir = tempdir().unwrap();
+        let runner = HookRunner::new(
+            tmp_dir.path().to_path_buf(),
+            vec![Env {
+                key: "WG_MOD_VERSION".to_string(),
+                value: "1.0.2".to_string(),
+            }],
+        );
+
+        runner
+            .run(
+                "pre_build",
+                &["echo $WG_MOD_VERSION > version.txt".to_string()],
+            )
+            .unwrap();
+        assert_eq!(
+            read_to_string(tmp_dir.path().join("version.txt")).unwrap(),
+            "1.0.2\n"
+        );
+
+        let error = runner
+            .run("post_build", &["echo lint failed && exit 3".to_string()])
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The post_build hook \"echo lint failed && exit 3\" failed\nlint failed"
+        );
+    }
+}
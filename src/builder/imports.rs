@@ -0,0 +1,335 @@
+use crate::builder::diagnostic::{Diagnostic, Severity};
+use glob::glob;
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to read {0}\n{1}")]
+    ReadError(PathBuf, io::Error),
+
+    #[error("File selection by pattern failed")]
+    GlobError(#[from] glob::GlobError),
+
+    #[error("Invalid pattern given")]
+    PatternError(#[from] glob::PatternError),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+// Package of the mod scripts once installed in the game
+pub const MODS_PACKAGE: &str = "gui.mods";
+
+// Dotted names of every module and package found under some roots
+#[derive(Default)]
+pub struct ModuleIndex {
+    modules: BTreeSet<String>,
+}
+
+fn python_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let pattern = root.join("**").join("*.py");
+    let pattern = pattern.to_string_lossy();
+
+    Ok(glob(&pattern)?.collect::<result::Result<Vec<_>, _>>()?)
+}
+
+fn module_name(root: &Path, file: &Path, prefix: &str) -> Option<String> {
+    let relative = file.strip_prefix(root).ok()?.with_extension("");
+    let mut parts = prefix
+        .split('.')
+        .filter(|part| !part.is_empty())
+        .map(String::from)
+        .collect::<Vec<_>>();
+    parts.extend(
+        relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into()),
+    );
+    if parts.last().is_some_and(|part| part == "__init__") {
+        parts.pop();
+    }
+
+    Some(parts.join("."))
+}
+
+impl ModuleIndex {
+    pub fn add_root(&mut self, root: &Path, prefix: &str) -> Result<()> {
+        for file in python_files(root)? {
+            if let Some(name) = module_name(root, &file, prefix) {
+                self.insert(&name);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Also used for modules generated during the build
+    pub fn insert(&mut self, name: &str) {
+        let mut parent = String::new();
+        for part in name.split('.') {
+            if !parent.is_empty() {
+                parent.push('.');
+            }
+            parent.push_str(part);
+            self.modules.insert(parent.clone());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.modules.contains(name)
+    }
+}
+
+enum Resolution {
+    Found,
+    // The top-level package isn't ours or the game's: stdlib or engine module
+    Unknown,
+    Missing(String),
+}
+
+// Python 2 tries a sibling module before the absolute one
+fn resolve_absolute(
+    index: &ModuleIndex, package: &[String], name: &str,
+) -> Resolution {
+    let sibling = package
+        .iter()
+        .cloned()
+        .chain([name.to_string()])
+        .collect::<Vec<_>>()
+        .join(".");
+    if index.contains(&sibling) || index.contains(name) {
+        return Resolution::Found;
+    }
+
+    let top_level = name.split('.').next().unwrap_or(name);
+    if index.contains(top_level) {
+        Resolution::Missing(name.to_string())
+    } else {
+        Resolution::Unknown
+    }
+}
+
+fn resolve_relative(
+    index: &ModuleIndex, package: &[String], level: usize, name: &str,
+) -> Resolution {
+    if level > package.len() + 1 {
+        return Resolution::Missing(format!("{}{name}", ".".repeat(level)));
+    }
+
+    let mut parts = package[..package.len() + 1 - level].to_vec();
+    if !name.is_empty() {
+        parts.push(name.to_string());
+    }
+    let absolute = parts.join(".");
+    if index.contains(&absolute) {
+        Resolution::Found
+    } else {
+        Resolution::Missing(absolute)
+    }
+}
+
+// An import nested in a try block usually has a fallback
+fn is_guarded(lines: &[&str], index: usize) -> bool {
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let current = indent(lines[index]);
+
+    lines[..index]
+        .iter()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .find(|line| indent(line) < current)
+        .is_some_and(|line| line.trim() == "try:")
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.split('#').next().unwrap_or(line).trim_end()
+}
+
+fn check_file(
+    content: &str, file: PathBuf, package: &[String], index: &ModuleIndex,
+) -> Vec<Diagnostic> {
+    let import = Regex::new(r"^(\s*)import\s+(.+)$").unwrap();
+    let from_import =
+        Regex::new(r"^(\s*)from\s+(\.*)([\w.]*)\s+import\b").unwrap();
+    let alias = Regex::new(r"\s+as\s+\w+$").unwrap();
+
+    let lines = content.lines().collect::<Vec<_>>();
+    let mut diagnostics = vec![];
+    let mut in_string = false;
+
+    for (number, line) in lines.iter().enumerate() {
+        let quotes =
+            line.matches("\"\"\"").count() + line.matches("'''").count();
+        if in_string || quotes > 0 {
+            if quotes % 2 == 1 {
+                in_string = !in_string;
+            }
+            continue;
+        }
+
+        let code = strip_comment(line);
+        let mut resolutions = vec![];
+        let column;
+        if let Some(captures) = from_import.captures(code) {
+            column = captures[1].len() + 1;
+            let level = captures[2].len();
+            let name = &captures[3];
+            if name == "__future__" {
+                continue;
+            }
+            resolutions.push(match level {
+                | 0 => resolve_absolute(index, package, name),
+                | _ => resolve_relative(index, package, level, name),
+            });
+        } else if let Some(captures) = import.captures(code) {
+            column = captures[1].len() + 1;
+            for name in captures[2].trim_end_matches('\\').split(',') {
+                let name = alias.replace(name.trim(), "");
+                if !name.is_empty() {
+                    resolutions.push(resolve_absolute(index, package, &name));
+                }
+            }
+        } else {
+            continue;
+        }
+
+        for resolution in resolutions {
+            let Resolution::Missing(name) = resolution else {
+                continue;
+            };
+            let mod_package = name
+                .strip_prefix(&format!("{MODS_PACKAGE}."))
+                .map(|name| name.split('.').next().unwrap_or(name));
+            let other_mod = mod_package.is_some_and(|mod_package| {
+                !index.contains(&format!("{MODS_PACKAGE}.{mod_package}"))
+            });
+            let severity = if other_mod || is_guarded(&lines, number) {
+                Severity::Warning
+            } else {
+                Severity::Error
+            };
+            let message = match (mod_package, other_mod) {
+                | (_, true) => format!(
+                    "No module named {name}, is it provided by another mod?"
+                ),
+                | (Some(_), false) => format!("No module named {name}"),
+                | (None, _) => {
+                    format!("No module named {name} in the game sources")
+                },
+            };
+
+            diagnostics.push(Diagnostic {
+                file: file.clone(),
+                line: Some(number + 1),
+                column: Some(column),
+                severity,
+                message,
+                snippet: vec![line.trim_end().to_string()],
+            });
+        }
+    }
+
+    diagnostics
+}
+
+// Imports are resolved like the game would, with the scripts installed in
+// gui/mods next to the game packages
+pub fn check_imports(
+    source: &Path, display_root: &Path, index: &ModuleIndex,
+) -> Result<Vec<Diagnostic>> {
+    let mut diagnostics = vec![];
+
+    for file in python_files(source)? {
+        let content = read_to_string(&file)
+            .map_err(|e| Error::ReadError(file.clone(), e))?;
+        let module =
+            module_name(source, &file, MODS_PACKAGE).unwrap_or_default();
+        let mut package =
+            module.split('.').map(String::from).collect::<Vec<_>>();
+        if !file.ends_with("__init__.py") {
+            package.pop();
+        }
+
+        let relative = file.strip_prefix(source).unwrap_or(&file);
+        diagnostics.extend(check_file(
+            &content,
+            display_root.join(relative),
+            &package,
+            index,
+        ));
+    }
+
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, write};
+    use tempfile::tempdir;
+
+    #[test]
+    fn report_missing_game_imports() {
+        let tmp_dir = tempdir().unwrap();
+        let game_root = tmp_dir.path().join("res/scripts/client");
+        create_dir_all(game_root.join("gui/shared")).unwrap();
+        write(game_root.join("gui/__init__.py"), "").unwrap();
+        write(game_root.join("gui/shared/__init__.py"), "").unwrap();
+        write(game_root.join("gui/shared/events.py"), "").unwrap();
+
+        let scripts = tmp_dir.path().join("scripts");
+        create_dir_all(scripts.join("my_mod")).unwrap();
+        write(scripts.join("my_mod/__init__.py"), "").unwrap();
+        write(scripts.join("my_mod/utils.py"), "").unwrap();
+        write(
+            scripts.join("mod_my_mod.py"),
+            "import os, BigWorld\n\
+             from gui.shared import events\n\
+             from gui.shared.removed import thing\n\
+             import gui.battle as battle  # gone\n\
+             from my_mod import utils\n\
+             from gui.mods.other_mod import api\n\
+             from gui.mods._build_info_my_mod import VERSION\n\
+             try:\n    import gui.optional\nexcept ImportError:\n    pass\n\
+             \"\"\"\nimport gui.in_docstring\n\"\"\"\n",
+        )
+        .unwrap();
+        write(
+            scripts.join("my_mod/feature.py"),
+            "from . import utils\nfrom .missing import x\nimport utils\n\
+             from .._build_info_my_mod import MOD_ID\n",
+        )
+        .unwrap();
+
+        let mut index = ModuleIndex::default();
+        index.add_root(&game_root, "").unwrap();
+        index.add_root(&scripts, MODS_PACKAGE).unwrap();
+        index.insert("gui.mods._build_info_my_mod");
+
+        let diagnostics =
+            check_imports(&scripts, Path::new("scripts"), &index).unwrap();
+        let summary = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .map(|text| text.lines().next().unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+            vec![
+                "scripts/mod_my_mod.py:3:1: error: No module named gui.shared.removed in the game sources",
+                "scripts/mod_my_mod.py:4:1: error: No module named gui.battle in the game sources",
+                "scripts/mod_my_mod.py:6:1: warning: No module named gui.mods.other_mod, is it provided by another mod?",
+                "scripts/mod_my_mod.py:9:5: warning: No module named gui.optional in the game sources",
+                "scripts/my_mod/feature.py:2:1: error: No module named gui.mods.my_mod.missing",
+            ]
+        );
+    }
+}
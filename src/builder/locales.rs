@@ -0,0 +1,91 @@
+use crate::builder::report;
+use crate::i18n;
+use std::path::PathBuf;
+use std::result;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Failed to compile translations\n{0}")]
+    I18nError(#[from] i18n::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub struct LocalesBuilder {
+    locale: Option<String>,
+}
+
+impl LocalesBuilder {
+    pub fn new(locale: Option<String>) -> Self {
+        Self { locale }
+    }
+
+    // Every locale goes to res/text/<lang>/LC_MESSAGES for mods loading
+    // their own catalogs, the selected one also to res/text/lc_messages
+    pub fn build(&self, source: &PathBuf, destination: &PathBuf) -> Result<()> {
+        let locales = i18n::list_locales(source)?;
+        let selected = i18n::select_locale(&locales, self.locale.as_deref())?;
+
+        for locale in &locales {
+            report::message(&format!(
+                "Compile translations for locale {locale}"
+            ));
+            i18n::compile_locale(
+                &source.join(locale),
+                &destination.join(locale).join("LC_MESSAGES"),
+            )?;
+        }
+
+        match selected {
+            | Some(locale) => {
+                i18n::compile_locale(
+                    &source.join(locale),
+                    &destination.join("lc_messages"),
+                )?;
+            },
+            | None if !locales.is_empty() => {
+                report::message(&format!(
+                    "Several locales found ({}), set \"locale\" in mod.json to choose the one installed in res/text/lc_messages",
+                    locales.join(", ")
+                ));
+            },
+            | None => {},
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, write};
+    use tempfile::tempdir;
+
+    #[test]
+    fn compile_every_locale() {
+        let tmp_dir = tempdir().unwrap();
+        let source = tmp_dir.path().join("locales");
+        for locale in ["de", "fr"] {
+            create_dir_all(source.join(locale)).unwrap();
+            write(
+                source.join(locale).join("my_mod.po"),
+                "msgid \"Hello\"\nmsgstr \"Salut\"\n",
+            )
+            .unwrap();
+        }
+
+        let destination = tmp_dir.path().join("text");
+        LocalesBuilder::new(None)
+            .build(&source, &destination)
+            .unwrap();
+        assert!(destination.join("de/LC_MESSAGES/my_mod.mo").exists());
+        assert!(destination.join("fr/LC_MESSAGES/my_mod.mo").exists());
+        assert!(!destination.join("lc_messages").exists());
+
+        LocalesBuilder::new(Some("fr".to_string()))
+            .build(&source, &destination)
+            .unwrap();
+        assert!(destination.join("lc_messages/my_mod.mo").exists());
+    }
+}
@@ -1,22 +1,44 @@
+pub mod build_info;
+mod bytecode;
+mod cache;
+mod diagnostic;
 mod flash;
+mod hooks;
+mod imports;
+mod locales;
 mod python;
+pub mod report;
+mod resources;
 
+use crate::builder::build_info::BuildInfo;
+use crate::builder::cache::BuildCache;
 use crate::builder::flash::FlashBuilder;
+use crate::builder::hooks::HookRunner;
+use crate::builder::locales::LocalesBuilder;
 use crate::builder::python::PythonBuilder;
+use crate::builder::report::{Event, StageStatus};
+use crate::builder::resources::ResourcesBuilder;
 use crate::config;
 use crate::config::asconfig_json::AsconfigcJson;
-use crate::config::mod_conf::ModConf;
-use crate::config::{get_tool_home, mod_conf};
+use crate::config::mod_conf::{ModConf, Profile, ResourceMapping, UiTarget};
+use crate::config::{get_tool_home, mod_conf, Configs};
 use crate::sdk::flash_lib;
 use crate::sdk::flash_lib::extract_flash_client_lib;
 use crate::utils::convert_pathbuf_to_string::Stringify;
 use crate::utils::convert_to_absolute_path::convert_to_absolute_path;
+use crate::utils::fingerprint::Fingerprint;
+use crate::utils::interactive;
 use crate::utils::zip;
-use crate::utils::{convert_pathbuf_to_string, convert_to_absolute_path};
+use crate::utils::{
+    convert_pathbuf_to_string, convert_to_absolute_path, fingerprint, Env,
+};
 use convert_case::{Case, Casing};
 use inquire::InquireError;
-use std::path::PathBuf;
-use std::{fs, io, result};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use std::{fs, io, panic, result, thread};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -61,32 +83,123 @@ pub enum Error {
 
     #[error("Failed build game client flash lib: {0}")]
     BuildFlashLibError(#[from] flash_lib::Error),
+
+    #[error("No main ui class found, pass --main-class or set \"main_class\" in mod.json")]
+    MissingMainClassError,
+
+    #[error("--main-class can't be used with \"ui_targets\", set \"main_class\" on each target in mod.json")]
+    MainClassWithUiTargetsError,
+
+    #[error("Unable to fingerprint build inputs\n{0}")]
+    FingerprintError(#[from] fingerprint::Error),
+
+    #[error("Unable to save the build cache\n{0}")]
+    BuildCacheError(#[from] cache::Error),
+
+    #[error("Failed to bundle resources\n{0}")]
+    ResourcesError(#[from] resources::Error),
+
+    #[error("Failed to build translations\n{0}")]
+    LocalesError(#[from] locales::Error),
+
+    #[error("Unable to serialize resources mapping\n{0}")]
+    SerializeError(#[from] serde_json::Error),
+
+    #[error("Invalid mod.json\n{0}")]
+    InvalidModConfError(mod_conf::Error),
+
+    #[error("{0}")]
+    HookError(#[from] hooks::Error),
+
+    #[error("Stage {0} cancelled")]
+    StageCancelledError(String),
+
+    #[error(
+        "{}",
+        .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+    )]
+    StagesFailedError(Vec<Error>),
 }
 
 type Result<T> = result::Result<T, Error>;
 
+// Shared by the stages of one build, python and flash run in parallel
+struct BuildState {
+    cache: Mutex<BuildCache>,
+    cancel: AtomicBool,
+}
+
+impl BuildState {
+    fn cache(&self) -> MutexGuard<'_, BuildCache> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// The error of a stage cancelled by the other one is only noise
+fn merge_stage_results(results: [Result<bool>; 2]) -> Result<bool> {
+    let mut changed = false;
+    let mut errors = vec![];
+    for result in results {
+        match result {
+            | Ok(stage_changed) => changed |= stage_changed,
+            | Err(Error::StageCancelledError(_)) => {},
+            | Err(e) => errors.push(e),
+        }
+    }
+
+    match errors.len() {
+        | 0 => Ok(changed),
+        | 1 => Err(errors.remove(0)),
+        | _ => Err(Error::StagesFailedError(errors)),
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BuildOptions {
+    pub main_class: Option<String>,
+    pub clean: bool,
+    pub output: Option<PathBuf>,
+    pub release: bool,
+}
+
 pub struct ModBuilder {
+    configs: Arc<Configs>,
     python_builder: PythonBuilder,
-    flash_builder: FlashBuilder,
     mod_path: PathBuf,
     target_path: PathBuf,
     build_path: PathBuf,
+    resources_path: PathBuf,
+    options: BuildOptions,
+}
+
+fn normalize_main_class(class: &str) -> String {
+    class
+        .trim()
+        .trim_end_matches(".as")
+        .replace(['/', '\\'], ".")
 }
 
 impl ModBuilder {
-    pub fn new(mod_path: PathBuf) -> Result<Self> {
-        let python_builder = PythonBuilder::new()?;
-        let flash_builder = FlashBuilder::new()?;
+    // The toolchain is loaded once by the command and shared by every build
+    pub fn with_configs(
+        mod_path: PathBuf, options: BuildOptions, configs: Arc<Configs>,
+    ) -> Self {
+        let python_builder = PythonBuilder::new(configs.clone());
         let target_path = mod_path.join("target");
         let build_path = target_path.join("build");
+        let resources_path = target_path.join("resources");
 
-        Ok(Self {
+        Self {
+            configs,
             python_builder,
-            flash_builder,
             mod_path,
             target_path,
             build_path,
-        })
+            resources_path,
+            options,
+        }
     }
 
     fn clean_target_directory(&self) -> Result<()> {
@@ -95,13 +208,153 @@ impl ModBuilder {
         Ok(())
     }
 
-    fn build_python_src(&self) -> Result<()> {
+    fn fingerprint(&self, inputs: &[PathBuf], salt: &str) -> Result<String> {
+        let mut fingerprint = Fingerprint::new();
+        fingerprint.add_str(salt);
+        for input in inputs {
+            fingerprint.add_path(input)?;
+        }
+
+        Ok(fingerprint.finish())
+    }
+
+    fn remove_output(&self, output: &PathBuf) -> Result<()> {
+        if output.is_dir() {
+            fs::remove_dir_all(output)?;
+        } else if output.exists() {
+            fs::remove_file(output)?;
+        }
+
+        Ok(())
+    }
+
+    fn run_stage(
+        &self, state: &BuildState, stage: &str, inputs: &[PathBuf], salt: &str,
+        output: &PathBuf, build: impl FnOnce() -> Result<()>,
+    ) -> Result<bool> {
+        let started = Instant::now();
+        report::emit(Event::StageStarted { stage });
+
+        let fingerprint = self.fingerprint(inputs, salt)?;
+        if output.exists() && state.cache().is_fresh(stage, &fingerprint) {
+            report::message(&format!("Stage {stage} is up to date"));
+            self.finish_stage(stage, StageStatus::Fresh, started);
+            return Ok(false);
+        }
+
+        {
+            let mut cache = state.cache();
+            cache.invalidate(stage);
+            cache.save()?;
+        }
+        self.remove_output(output)?;
+
+        if let Err(e) = build() {
+            // Another stage failed first and asked this one to stop
+            if state.cancel.swap(true, Ordering::SeqCst) {
+                self.finish_stage(stage, StageStatus::Cancelled, started);
+                return Err(Error::StageCancelledError(stage.to_string()));
+            }
+            self.finish_stage(stage, StageStatus::Failed, started);
+            return Err(e);
+        }
+
+        // Inputs edited while the stage ran are built again next time
+        let mut cache = state.cache();
+        cache.update(stage, fingerprint);
+        cache.save()?;
+        self.finish_stage(stage, StageStatus::Built, started);
+
+        Ok(true)
+    }
+
+    fn finish_stage(&self, stage: &str, status: StageStatus, started: Instant) {
+        report::emit(Event::StageFinished {
+            stage,
+            status,
+            duration_ms: started.elapsed().as_millis(),
+        });
+    }
+
+    fn skip_stage(
+        &self, state: &BuildState, stage: &str, output: &PathBuf,
+    ) -> Result<bool> {
+        let had_output = output.exists();
+        self.finish_stage(stage, StageStatus::Skipped, Instant::now());
+
+        {
+            let mut cache = state.cache();
+            cache.invalidate(stage);
+            cache.save()?;
+        }
+        self.remove_output(output)?;
+
+        Ok(had_output)
+    }
+
+    fn python_output(&self) -> PathBuf {
+        self.build_path.join("res/scripts/client/gui/mods")
+    }
+
+    fn flash_output(&self) -> PathBuf {
+        self.build_path.join("res/gui/flash")
+    }
+
+    fn flash_inputs(&self) -> Result<Vec<PathBuf>> {
+        Ok(vec![
+            self.mod_path.join("ui"),
+            self.mod_path.join("mod.json"),
+            get_tool_home()?.join("flash_lib"),
+        ])
+    }
+
+    fn resource_mappings(&self, mod_conf: &ModConf) -> Vec<ResourceMapping> {
+        let mut mappings = vec![];
+
+        if self.mod_path.join("res").is_dir() {
+            mappings.push(ResourceMapping {
+                source: PathBuf::from("res"),
+                ..Default::default()
+            });
+        }
+        mappings.extend(mod_conf.resources.iter().cloned());
+
+        mappings
+    }
+
+    fn build_resources(&self, mappings: &[ResourceMapping]) -> Result<()> {
+        let resources_builder = ResourcesBuilder::new(mappings.to_vec());
+        resources_builder
+            .build(&self.mod_path, &self.resources_path.join("res"))?;
+
+        Ok(())
+    }
+
+    fn locales_output(&self) -> PathBuf {
+        self.build_path.join("res/text")
+    }
+
+    fn build_locales(&self, locale: Option<String>) -> Result<()> {
+        let locales_builder = LocalesBuilder::new(locale);
+        locales_builder
+            .build(&self.mod_path.join("locales"), &self.locales_output())?;
+
+        Ok(())
+    }
+
+    fn build_python_src(
+        &self, build_info: &BuildInfo, profile: Profile, cancel: &AtomicBool,
+    ) -> Result<()> {
         let python_sources = self.mod_path.join("scripts");
-        let python_build_destination =
-            self.build_path.join("res/scripts/client/gui/mods");
+        let python_build_destination = self.python_output();
 
-        self.python_builder
-            .build(&python_sources, &python_build_destination)?;
+        self.python_builder.build(
+            &python_sources,
+            &python_build_destination,
+            build_info,
+            profile,
+            cancel,
+        )?;
 
         Ok(())
     }
@@ -113,9 +366,19 @@ impl ModBuilder {
 
         Ok(())
     }
-    fn build_flash_src(&self) -> Result<()> {
+
+    fn has_flash_sources(&self, mod_conf: &ModConf) -> bool {
+        match mod_conf.ui {
+            | Some(enabled) => enabled,
+            | None => self.mod_path.join("ui").exists(),
+        }
+    }
+
+    fn build_flash_src(
+        &self, build_info: &BuildInfo, cancel: &AtomicBool,
+    ) -> Result<()> {
         let flash_sources = self.mod_path.join("ui");
-        let flash_build_destination = self.build_path.join("res/gui/flash");
+        let flash_build_destination = self.flash_output();
 
         if !flash_sources.exists() {
             Err(Error::PathError(format!(
@@ -125,65 +388,143 @@ impl ModBuilder {
         }
 
         let wg_home = get_tool_home()?;
-        extract_flash_client_lib(&wg_home)?;
+        extract_flash_client_lib(&wg_home, cancel)?;
 
-        self.update_asconfigc_json(&flash_sources)?;
+        let flash_builder = FlashBuilder::new(self.configs.clone());
+        for target in self.ui_targets(&flash_sources)? {
+            let config_path = flash_sources.join(target.config_path());
+            let build_config_path =
+                self.update_asconfigc_json(&config_path, &target, build_info)?;
 
-        self.flash_builder
-            .build(&flash_sources, &flash_build_destination)?;
+            report::message(&format!(
+                "Build flash target {}",
+                target.output_file_name()
+            ));
+            flash_builder.build(
+                &build_config_path,
+                &self.mod_path,
+                &flash_build_destination,
+                cancel,
+            )?;
+        }
 
         Ok(())
     }
 
-    fn update_asconfigc_json(&self, flash_sources: &PathBuf) -> Result<()> {
+    fn ui_targets(&self, flash_sources: &PathBuf) -> Result<Vec<UiTarget>> {
+        let meta_path = self.mod_path.join("mod.json");
+        let mut mod_conf = ModConf::from_file(&meta_path)?;
+
+        if !mod_conf.ui_targets.is_empty() {
+            if self.options.main_class.is_some() {
+                Err(Error::MainClassWithUiTargetsError)?
+            }
+            return Ok(mod_conf.ui_targets);
+        }
+
         let asconfigc_json_path = flash_sources.join("asconfig.json");
-        let mut asconfigc = AsconfigcJson::from_file(&asconfigc_json_path)?;
+        let asconfigc = AsconfigcJson::from_file(&asconfigc_json_path)?;
+        let main_class = self.resolve_main_class(&mod_conf, &asconfigc)?;
 
-        let main_class_string_path = asconfigc.main_class.replace(".", "/");
+        if mod_conf.main_class.as_ref() != Some(&main_class) {
+            mod_conf.main_class = Some(main_class.clone());
+            mod_conf.write_json_to_file(&meta_path)?;
+        }
 
-        let prompt_class_string =
-            self.prompt_main_class(main_class_string_path.as_str())?;
+        Ok(vec![UiTarget {
+            main_class,
+            output: mod_conf.name.to_case(Case::Snake),
+            config: None,
+        }])
+    }
 
-        let prompt_class_with_extention = if prompt_class_string.contains(".as")
-        {
-            &prompt_class_string
-        } else {
-            &format!("{}.as", &prompt_class_string)
-        };
-        let prompt_class_globale_path = self
-            .mod_path
-            .join("ui")
-            .join("src")
-            .join(&prompt_class_with_extention);
-        if !prompt_class_globale_path.exists() {
-            let error_message = format!("Prompt class path given ({:?}) does not exist (File not found)", prompt_class_globale_path.to_string());
+    // Returns the config given to asconfigc, a copy under target/ holding
+    // the build defines
+    fn update_asconfigc_json(
+        &self, config_path: &PathBuf, target: &UiTarget, build_info: &BuildInfo,
+    ) -> Result<PathBuf> {
+        let mut asconfigc = AsconfigcJson::from_file(config_path)?;
+        let config_directory = config_path
+            .parent()
+            .ok_or(Error::PathError(config_path.to_string()?))?;
+
+        let main_class = normalize_main_class(&target.main_class);
+        let main_class_file = format!("{}.as", main_class.replace(".", "/"));
+        let main_class_exists =
+            asconfigc.compiler_option.source_path.iter().any(|source| {
+                config_directory
+                    .join(source)
+                    .join(&main_class_file)
+                    .exists()
+            });
+        if !main_class_exists {
+            let error_message = format!(
+                "Main class path given ({:?}) does not exist (File not found)",
+                main_class_file
+            );
             Err(Error::PathError(error_message))?
         }
+        asconfigc.main_class = main_class;
 
-        let prompt_class_without_extention =
-            if prompt_class_string.contains(".as") {
-                &prompt_class_string.replace(".as", "")
-            } else {
-                &prompt_class_string
-            };
-        let prompt_class = prompt_class_without_extention.replace("/", ".");
-        if !prompt_class.eq(asconfigc.main_class.as_str()) {
-            asconfigc.main_class = prompt_class;
+        // The output is relative to the directory of the config file
+        let depth = config_directory
+            .strip_prefix(&self.mod_path)
+            .map(|path| path.components().count())
+            .unwrap_or(1);
+        let mut output = PathBuf::new();
+        for _ in 0..depth {
+            output.push("..");
         }
-
-        let meta_path = self.mod_path.join("mod.json");
-        let mod_conf = ModConf::from_file(&meta_path)?;
-        let output = format!("{}.swf", mod_conf.name.to_case(Case::Snake));
-        asconfigc.compiler_option.output = PathBuf::from("..")
-            .join(&self.build_path)
+        let build_path = self
+            .build_path
+            .strip_prefix(&self.mod_path)
+            .unwrap_or(&self.build_path);
+        asconfigc.compiler_option.output = output
+            .join(build_path)
             .join("res")
             .join("gui")
             .join("flash")
-            .join(output)
+            .join(target.output_file_name())
             .to_string()?;
-        asconfigc.write_json_to_file(&asconfigc_json_path)?;
+        asconfigc.write_json_to_file(config_path)?;
 
-        Ok(())
+        // The defines change with every commit, the user's config is left
+        // alone so it doesn't get dirty on each build
+        let build_config_path = self
+            .target_path
+            .join("flash")
+            .join(format!("{}.asconfig.json", target.output_file_name()));
+        fs::create_dir_all(self.target_path.join("flash"))?;
+        asconfigc.resolve_paths(config_directory)?;
+        asconfigc.set_defines(build_info.flash_defines());
+        asconfigc.write_json_to_file(&build_config_path)?;
+
+        Ok(build_config_path)
+    }
+
+    fn resolve_main_class(
+        &self, mod_conf: &ModConf, asconfigc: &AsconfigcJson,
+    ) -> Result<String> {
+        if let Some(main_class) = &self.options.main_class {
+            return Ok(normalize_main_class(main_class));
+        }
+
+        if let Some(main_class) = &mod_conf.main_class {
+            return Ok(normalize_main_class(main_class));
+        }
+
+        if !interactive::is_interactive() {
+            if asconfigc.main_class.is_empty() {
+                Err(Error::MissingMainClassError)?
+            }
+
+            return Ok(normalize_main_class(&asconfigc.main_class));
+        }
+
+        let default = asconfigc.main_class.replace(".", "/");
+        let prompt_class = self.prompt_main_class(default.as_str())?;
+
+        Ok(normalize_main_class(&prompt_class))
     }
 
     fn prompt_main_class(&self, default: &str) -> Result<String> {
@@ -196,28 +537,278 @@ impl ModBuilder {
         Ok(value)
     }
 
-    fn make_archive(&self) -> Result<PathBuf> {
-        let archive_file = self.target_path.join("result.wotmod");
-        zip::archive_directory(&archive_file, &self.build_path)?;
+    // --release wins over the profile of mod.json
+    fn profile(&self, mod_conf: &ModConf) -> Profile {
+        if self.options.release {
+            return Profile::Release;
+        }
+
+        mod_conf.profile.unwrap_or_default()
+    }
+
+    fn archive_path(&self, mod_conf: &ModConf) -> Result<PathBuf> {
+        if let Some(output) = &self.options.output {
+            return Ok(output.clone());
+        }
+
+        Ok(self.target_path.join(mod_conf.archive_file_name()?))
+    }
+
+    fn hook_runner(
+        &self, mod_conf: &ModConf, archive_path: &PathBuf,
+    ) -> Result<HookRunner> {
+        let game_version = self
+            .configs
+            .game_client
+            .as_ref()
+            .and_then(|game_client| game_client.version().ok())
+            .unwrap_or_default();
+        let env = |key: &str, value: String| Env {
+            key: key.to_string(),
+            value,
+        };
+        // The build dir and archive may not exist yet, so no canonicalize
+        let absolute = |path: &PathBuf| -> Result<String> {
+            Ok(std::path::absolute(path)?.to_string_lossy().to_string())
+        };
 
-        Ok(archive_file)
+        Ok(HookRunner::new(
+            self.mod_path.clone(),
+            vec![
+                env("WG_MOD_PATH", convert_to_absolute_path(&self.mod_path)?),
+                env("WG_MOD_BUILD_DIR", absolute(&self.build_path)?),
+                env("WG_MOD_ARCHIVE", absolute(archive_path)?),
+                env("WG_MOD_ID", mod_conf.package_name.clone()),
+                env("WG_MOD_VERSION", mod_conf.version.clone()),
+                env("WG_MOD_GAME_VERSION", game_version),
+            ],
+        ))
     }
 
-    pub fn build(&self) -> Result<()> {
+    fn make_archive(&self, archive_file: &PathBuf) -> Result<()> {
+        if let Some(parent) = archive_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Skipped stages leave no output directory behind
+        let directories = [&self.build_path, &self.resources_path]
+            .into_iter()
+            .filter(|directory| directory.is_dir())
+            .cloned()
+            .collect::<Vec<_>>();
+        zip::archive_directories(archive_file, &directories)?;
+
+        Ok(())
+    }
+
+    // Relative to the mod folder, mod.json is read again on every call so
+    // resource mappings added since the last build are watched too
+    fn watched_paths(&self) -> Vec<PathBuf> {
+        let mut paths = ["scripts", "ui/src", "mod.json", "res", "locales"]
+            .map(PathBuf::from)
+            .to_vec();
+
+        if let Ok(mod_conf) =
+            ModConf::from_file(&self.mod_path.join("mod.json"))
+        {
+            paths.extend(mod_conf.resources.iter().map(|mapping| {
+                mapping
+                    .source
+                    .components()
+                    .filter(|component| *component != Component::CurDir)
+                    .collect()
+            }));
+        }
+
+        paths
+    }
+
+    pub fn is_watched(&self, relative_path: &Path) -> bool {
+        // Written by the build itself
+        if relative_path.starts_with("target")
+            || relative_path.starts_with("build")
+        {
+            return false;
+        }
+
+        self.watched_paths()
+            .iter()
+            .any(|path| relative_path.starts_with(path))
+    }
+
+    // Relative to the mod folder, the sources a build may rewrite: the main
+    // class saved into mod.json and the asconfig.json of each ui target
+    pub fn rewritten_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![PathBuf::from("mod.json")];
+
+        let targets = ModConf::from_file(&self.mod_path.join("mod.json"))
+            .map(|mod_conf| mod_conf.ui_targets)
+            .unwrap_or_default();
+        if targets.is_empty() {
+            paths.push(PathBuf::from("ui/asconfig.json"));
+        }
+        paths.extend(
+            targets
+                .iter()
+                .map(|target| Path::new("ui").join(target.config_path())),
+        );
+
+        paths
+    }
+
+    pub fn build(&self) -> Result<PathBuf> {
+        let started = Instant::now();
         self.throw_if_isn_t_mod_folder()?;
 
-        self.clean_target_directory()?;
+        if self.options.clean {
+            self.clean_target_directory()?;
+        }
+
+        let mod_conf = ModConf::from_file(&self.mod_path.join("mod.json"))?;
+        mod_conf
+            .validate_dependencies()
+            .map_err(Error::InvalidModConfError)?;
+
+        let archive_path = self.archive_path(&mod_conf)?;
+        let hooks = self.hook_runner(&mod_conf, &archive_path)?;
+        hooks.run("pre_build", &mod_conf.hooks.pre_build)?;
+
+        let profile = self.profile(&mod_conf);
+        let build_info = BuildInfo::new(
+            &mod_conf,
+            &self.mod_path,
+            profile == Profile::Debug,
+        );
+
+        let state = BuildState {
+            cache: Mutex::new(BuildCache::load(&self.target_path)),
+            cancel: AtomicBool::new(false),
+        };
+        let mut changed = false;
+
+        // Imports are checked against the checked out game sources
+        let python_salt = format!(
+            "{}{}",
+            self.configs.game_sources.revision().unwrap_or_default(),
+            build_info.python_module()
+        );
+        let python_sources = self.mod_path.join("scripts");
+        let python_stage = || {
+            if !python_sources.is_dir() {
+                report::message("No scripts found, skipping python build");
+                return self.skip_stage(
+                    &state,
+                    "python",
+                    &self.python_output(),
+                );
+            }
+
+            self.run_stage(
+                &state,
+                "python",
+                &[python_sources.clone()],
+                &python_salt,
+                &self.python_output(),
+                || self.build_python_src(&build_info, profile, &state.cancel),
+            )
+        };
+
+        let flash_stage = if self.has_flash_sources(&mod_conf) {
+            let inputs = self.flash_inputs()?;
+            let salt = format!(
+                "{}{}",
+                self.options.main_class.clone().unwrap_or_default(),
+                serde_json::to_string(&build_info.flash_defines())?
+            );
+            Some((inputs, salt))
+        } else {
+            report::message("No ui sources found, skipping flash build");
+            None
+        };
+        let flash_stage = || match &flash_stage {
+            | Some((inputs, salt)) => self.run_stage(
+                &state,
+                "flash",
+                inputs,
+                salt,
+                &self.flash_output(),
+                || self.build_flash_src(&build_info, &state.cancel),
+            ),
+            | None => self.skip_stage(&state, "flash", &self.flash_output()),
+        };
+
+        // Both stages write to their own build/res subtree
+        let results = thread::scope(|scope| {
+            let flash = scope.spawn(flash_stage);
+            let python = python_stage();
+            let flash =
+                flash.join().unwrap_or_else(|e| panic::resume_unwind(e));
+            [python, flash]
+        });
+        changed |= merge_stage_results(results)?;
+
+        let mappings = self.resource_mappings(&mod_conf);
+        if mappings.is_empty() {
+            changed |=
+                self.skip_stage(&state, "resources", &self.resources_path)?;
+        } else {
+            let inputs = mappings
+                .iter()
+                .map(|mapping| self.mod_path.join(&mapping.source))
+                .collect::<Vec<_>>();
+            changed |= self.run_stage(
+                &state,
+                "resources",
+                &inputs,
+                &serde_json::to_string(&mappings)?,
+                &self.resources_path,
+                || self.build_resources(&mappings),
+            )?;
+        }
+
+        let locales_path = self.mod_path.join("locales");
+        if locales_path.is_dir() {
+            let locale = mod_conf.locale.clone();
+            changed |= self.run_stage(
+                &state,
+                "locales",
+                &[locales_path],
+                &locale.clone().unwrap_or_default(),
+                &self.locales_output(),
+                || self.build_locales(locale),
+            )?;
+        } else {
+            changed |=
+                self.skip_stage(&state, "locales", &self.locales_output())?;
+        }
 
-        self.build_python_src()?;
-        self.copy_meta_file()?;
+        changed |= self.run_stage(
+            &state,
+            "meta",
+            &[self.mod_path.join("mod.json")],
+            "",
+            &self.build_path.join("meta.xml"),
+            || self.copy_meta_file(),
+        )?;
 
-        self.build_flash_src()?;
+        if changed || !archive_path.exists() {
+            self.make_archive(&archive_path)?;
+        } else {
+            report::message("Archive is up to date");
+        }
+        hooks.run("post_build", &mod_conf.hooks.post_build)?;
 
-        let archive_path = self.make_archive()?;
         let absolute_build_path = convert_to_absolute_path(&archive_path)?;
-        println!("Build finished: {}", absolute_build_path);
+        if report::is_json_output() {
+            report::emit(Event::BuildFinished {
+                archive: &absolute_build_path,
+                size: fs::metadata(&archive_path)?.len(),
+                duration_ms: started.elapsed().as_millis(),
+            });
+        } else {
+            println!("Build finished: {}", absolute_build_path);
+        }
 
-        Ok(())
+        Ok(archive_path)
     }
 
     fn throw_if_isn_t_mod_folder(&self) -> Result<()> {
@@ -237,3 +828,353 @@ impl ModBuilder {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::mock_configs;
+    use std::env;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    const JSON_EXPORT_CHILD: &str = "WG_MOD_JSON_EXPORT_CHILD";
+    const EVENTS_BEGIN: &str = "--- events begin ---";
+    const EVENTS_END: &str = "--- events end ---";
+
+    #[test]
+    fn normalize_main_class_formats() {
+        assert_eq!(
+            normalize_main_class("fr/gabouchet/Main"),
+            "fr.gabouchet.Main"
+        );
+        assert_eq!(
+            normalize_main_class("fr/gabouchet/Main.as"),
+            "fr.gabouchet.Main"
+        );
+        assert_eq!(
+            normalize_main_class("fr.gabouchet.Main"),
+            "fr.gabouchet.Main"
+        );
+    }
+
+    #[test]
+    fn merge_parallel_stage_results() {
+        let cancelled = || Err(Error::StageCancelledError("flash".to_string()));
+        let failed = || Err(Error::PathError("scripts".to_string()));
+
+        assert!(merge_stage_results([Ok(false), Ok(true)]).unwrap());
+        assert!(matches!(
+            merge_stage_results([failed(), cancelled()]),
+            Err(Error::PathError(_))
+        ));
+        assert!(matches!(
+            merge_stage_results([failed(), failed()]),
+            Err(Error::StagesFailedError(errors)) if errors.len() == 2
+        ));
+    }
+
+    // The test harness captures println!, so the export runs in a child
+    // test process and its real stdout is checked
+    #[test]
+    fn json_export_only_prints_events() {
+        if let Some(mod_path) = env::var_os(JSON_EXPORT_CHILD) {
+            let mod_path = PathBuf::from(mod_path);
+            let configs = Arc::new(mock_configs(&mod_path.join("home")));
+            report::set_json_output(true);
+
+            println!("\n{EVENTS_BEGIN}");
+            ModBuilder::with_configs(
+                mod_path,
+                BuildOptions::default(),
+                configs,
+            )
+            .build()
+            .unwrap();
+            println!("{EVENTS_END}");
+            return;
+        }
+
+        let tmp_dir = tempdir().unwrap();
+        let mod_path = tmp_dir.path().to_path_buf();
+        fs::write(
+            mod_path.join("mod.json"),
+            r#"{
+                "id": "com.example.mod",
+                "version": "1.0.0",
+                "name": "Mod",
+                "description": "",
+                "ui": false,
+                "hooks": { "pre_build": ["echo hook output"] }
+            }"#,
+        )
+        .unwrap();
+
+        let output = Command::new(env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "builder::tests::json_export_only_prints_events",
+                "--nocapture",
+                "--test-threads=1",
+            ])
+            .env(JSON_EXPORT_CHILD, &mod_path)
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            output.status.success(),
+            "{stdout}\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let events = stdout
+            .lines()
+            .skip_while(|line| *line != EVENTS_BEGIN)
+            .skip(1)
+            .take_while(|line| *line != EVENTS_END)
+            .collect::<Vec<_>>();
+        let reasons = [
+            "stage-started",
+            "stage-finished",
+            "diagnostic",
+            "message",
+            "build-finished",
+            "build-failed",
+            "member-finished",
+        ];
+        for line in &events {
+            let event: serde_json::Value = serde_json::from_str(line)
+                .unwrap_or_else(|_| panic!("Not a JSON event: {line}"));
+            let reason = event["reason"].as_str().unwrap_or_default();
+            assert!(reasons.contains(&reason), "Unknown event: {line}");
+        }
+        assert!(events.iter().any(|line| line.contains("hook output")));
+        assert!(events.last().unwrap().contains("build-finished"));
+    }
+
+    #[test]
+    fn reject_main_class_with_ui_targets() {
+        let tmp_dir = tempdir().unwrap();
+        let mod_path = tmp_dir.path().to_path_buf();
+        let configs = Arc::new(mock_configs(&mod_path.join("home")));
+        fs::write(
+            mod_path.join("mod.json"),
+            r#"{
+                "id": "com.example.mod",
+                "version": "1.0.0",
+                "name": "Mod",
+                "description": "",
+                "ui_targets": [{ "main_class": "my.mod.Main", "output": "main" }]
+            }"#,
+        )
+        .unwrap();
+
+        let options = BuildOptions {
+            main_class: Some("my.mod.Other".to_string()),
+            ..Default::default()
+        };
+        let mod_builder =
+            ModBuilder::with_configs(mod_path.clone(), options, configs);
+
+        assert!(matches!(
+            mod_builder.ui_targets(&mod_path.join("ui")),
+            Err(Error::MainClassWithUiTargetsError)
+        ));
+    }
+
+    #[test]
+    fn keep_flash_defines_out_of_the_user_config() {
+        let tmp_dir = tempdir().unwrap();
+        let mod_path = tmp_dir.path().to_path_buf();
+        let configs = Arc::new(mock_configs(&mod_path.join("home")));
+        let config_path = mod_path.join("ui/asconfig.json");
+        fs::create_dir_all(mod_path.join("ui/src/my/mod")).unwrap();
+        fs::write(mod_path.join("ui/src/my/mod/Main.as"), "").unwrap();
+        fs::write(
+            &config_path,
+            r#"{
+                "config": "flex",
+                "compilerOptions": {
+                    "output": "main.swf",
+                    "source-path": ["src"],
+                    "external-library-path": []
+                },
+                "mainClass": "my.mod.Main"
+            }"#,
+        )
+        .unwrap();
+
+        let mod_builder = ModBuilder::with_configs(
+            mod_path.clone(),
+            BuildOptions::default(),
+            configs,
+        );
+        let target = UiTarget {
+            main_class: "my.mod.Main".to_string(),
+            output: "main".to_string(),
+            config: None,
+        };
+        let build_info = BuildInfo {
+            id: "com.example.mod".to_string(),
+            name: "Mod".to_string(),
+            version: "1.0.0".to_string(),
+            commit: Some("0123abcd".to_string()),
+            debug: true,
+        };
+        let build_config_path = mod_builder
+            .update_asconfigc_json(&config_path, &target, &build_info)
+            .unwrap();
+
+        let user_config = AsconfigcJson::from_file(&config_path).unwrap();
+        assert!(user_config.compiler_option.define.is_empty());
+
+        let build_config =
+            AsconfigcJson::from_file(&build_config_path).unwrap();
+        assert!(build_config_path.starts_with(mod_path.join("target")));
+        assert!(build_config
+            .compiler_option
+            .define
+            .iter()
+            .any(|define| define.name == "CONFIG::commit"));
+        assert_eq!(
+            PathBuf::from(&build_config.compiler_option.source_path[0]),
+            std::path::absolute(mod_path.join("ui/src")).unwrap()
+        );
+    }
+
+    #[test]
+    fn rebuild_inputs_edited_during_a_stage() {
+        let tmp_dir = tempdir().unwrap();
+        let mod_path = tmp_dir.path().to_path_buf();
+        let configs = Arc::new(mock_configs(&mod_path.join("home")));
+        let mod_builder = ModBuilder::with_configs(
+            mod_path.clone(),
+            BuildOptions::default(),
+            configs,
+        );
+        let input = mod_path.join("scripts");
+        let output = mod_path.join("target/build/scripts");
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("mod_test.py"), "print 'a'").unwrap();
+
+        let state = BuildState {
+            cache: Mutex::new(BuildCache::load(&mod_builder.target_path)),
+            cancel: AtomicBool::new(false),
+        };
+        let inputs = [input.clone()];
+        let built = mod_builder
+            .run_stage(&state, "python", &inputs, "", &output, || {
+                fs::create_dir_all(&output)?;
+                fs::write(input.join("mod_test.py"), "print 'b'")?;
+                Ok(())
+            })
+            .unwrap();
+        assert!(built);
+
+        let built = mod_builder
+            .run_stage(&state, "python", &inputs, "", &output, || Ok(()))
+            .unwrap();
+        assert!(built);
+    }
+
+    #[test]
+    fn watch_sources_and_resource_mappings() {
+        let tmp_dir = tempdir().unwrap();
+        let mod_path = tmp_dir.path().to_path_buf();
+        let configs = Arc::new(mock_configs(&mod_path.join("home")));
+        let mod_builder = ModBuilder::with_configs(
+            mod_path.clone(),
+            BuildOptions::default(),
+            configs,
+        );
+        fs::write(
+            mod_path.join("mod.json"),
+            r#"{
+                "id": "com.example.mod",
+                "version": "1.0.0",
+                "name": "Mod",
+                "description": "",
+                "resources": [{ "source": "./assets" }]
+            }"#,
+        )
+        .unwrap();
+
+        for path in ["mod.json", "scripts/mod_test.py", "assets/icon.png"] {
+            assert!(mod_builder.is_watched(Path::new(path)), "{path}");
+        }
+        for path in ["target/build/meta.xml", "ui/build/mod.swf", "README.md"] {
+            assert!(!mod_builder.is_watched(Path::new(path)), "{path}");
+        }
+    }
+
+    #[test]
+    fn list_sources_rewritten_by_the_build() {
+        let tmp_dir = tempdir().unwrap();
+        let mod_path = tmp_dir.path().to_path_buf();
+        let configs = Arc::new(mock_configs(&mod_path.join("home")));
+        let mod_builder = ModBuilder::with_configs(
+            mod_path.clone(),
+            BuildOptions::default(),
+            configs,
+        );
+        fs::write(
+            mod_path.join("mod.json"),
+            r#"{
+                "id": "com.example.mod",
+                "version": "1.0.0",
+                "name": "Mod",
+                "description": "",
+                "ui_targets": [
+                    { "main_class": "my.mod.Main", "output": "main" },
+                    { "main_class": "my.mod.Battle", "output": "battle", "config": "src/battle/asconfig.json" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            mod_builder.rewritten_paths(),
+            vec![
+                PathBuf::from("mod.json"),
+                PathBuf::from("ui/asconfig.json"),
+                PathBuf::from("ui/src/battle/asconfig.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn export_is_reproducible() {
+        let tmp_dir = tempdir().unwrap();
+        let mod_path = tmp_dir.path().to_path_buf();
+        let configs = Arc::new(mock_configs(&mod_path.join("home")));
+        fs::write(
+            mod_path.join("mod.json"),
+            r#"{
+                "id": "com.example.mod",
+                "version": "1.0.0",
+                "name": "Mod",
+                "description": "",
+                "ui": false
+            }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(mod_path.join("res/gui/maps")).unwrap();
+        fs::write(mod_path.join("res/gui/maps/icon.png"), "png").unwrap();
+
+        let build = |output: &str| {
+            let options = BuildOptions {
+                clean: true,
+                output: Some(mod_path.join(output)),
+                ..Default::default()
+            };
+            ModBuilder::with_configs(mod_path.clone(), options, configs.clone())
+                .build()
+                .unwrap()
+        };
+        let first = build("first.wotmod");
+        // Files written by the second build get other modification times
+        thread::sleep(std::time::Duration::from_millis(1100));
+        let second = build("second.wotmod");
+
+        assert_eq!(fs::read(first).unwrap(), fs::read(second).unwrap());
+    }
+}
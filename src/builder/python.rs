@@ -1,13 +1,27 @@
+use crate::builder::build_info::BuildInfo;
+use crate::builder::bytecode::check_bytecode;
+use crate::builder::diagnostic::{Diagnostic, Diagnostics, Severity};
+use crate::builder::imports::{check_imports, ModuleIndex, MODS_PACKAGE};
+use crate::builder::{bytecode, imports, report};
 use crate::config;
+use crate::config::mod_conf::Profile;
 use crate::config::Configs;
 use crate::sdk::conda;
 use crate::sdk::conda::environment::CondaEnvironment;
+use crate::sdk::game_sources;
 use crate::utils::copy_directory;
 use crate::utils::copy_directory::copy_directory;
 use crate::utils::tmp_dir::TempDirError;
+use crate::utils::zip::source_date_epoch;
 use glob::glob;
-use std::fs::{create_dir_all, remove_file};
-use std::path::PathBuf;
+use regex::Regex;
+use std::fs::{
+    create_dir_all, remove_dir_all, remove_file, rename, write, File,
+};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
 use std::{io, result};
 use tempfile::tempdir;
 
@@ -42,29 +56,73 @@ pub enum Error {
 
     #[error("Tempory directory usage failed")]
     TempDirError(#[from] TempDirError),
+
+    #[error("Python compilation failed\n{0}")]
+    CompileError(Diagnostics),
+
+    #[error("Invalid Python bytecode\n{0}")]
+    BytecodeError(Diagnostics),
+
+    #[error("Unable to check Python bytecode\n{0}")]
+    BytecodeCheckError(#[from] bytecode::Error),
+
+    #[error("Unresolved Python imports\n{0}")]
+    ImportError(Diagnostics),
+
+    #[error("Unable to index Python modules\n{0}")]
+    ModuleIndexError(#[from] imports::Error),
+
+    #[error("Unable to list the game Python sources\n{0}")]
+    GameSourcesError(#[from] game_sources::Error),
+
+    #[error("Python compilation failed\n{0}")]
+    CompileOutputError(String),
 }
 
 type Result<T> = result::Result<T, Error>;
 
+// Where the scripts live in the game, used as co_filename instead of the
+// temporary directory so builds are reproducible
+const SCRIPTS_DISPLAY_PATH: &str = "scripts/client/gui/mods";
+
 pub struct PythonBuilder {
-    conda_environment: CondaEnvironment,
+    configs: Arc<Configs>,
 }
 
 impl PythonBuilder {
-    pub fn new() -> Result<Self> {
-        let configs = Configs::load()?;
+    pub fn new(configs: Arc<Configs>) -> Self {
+        Self { configs }
+    }
 
-        Ok(Self {
-            conda_environment: configs.conda_environment,
-        })
+    fn conda_environment(&self) -> &CondaEnvironment {
+        &self.configs.conda_environment
     }
 
-    pub fn build(&self, source: &PathBuf, destination: &PathBuf) -> Result<()> {
+    pub fn build(
+        &self, source: &PathBuf, destination: &PathBuf, build_info: &BuildInfo,
+        profile: Profile, cancel: &AtomicBool,
+    ) -> Result<()> {
+        self.check_imports(source, build_info)?;
+
         let (close_tmp_dir, tmp_dir_path) = self.prepare_tmp_directory()?;
 
         copy_directory(source, &tmp_dir_path)?;
+        self.remove_pycache(&tmp_dir_path)?;
+        write(
+            tmp_dir_path.join(format!("{}.py", build_info.module_name())),
+            build_info.python_module(),
+        )
+        .map_err(Error::WriteFilesError)?;
+        self.stamp_sources(&tmp_dir_path)?;
 
-        self.conda_environment.compile_all(&tmp_dir_path)?;
+        let release = profile == Profile::Release;
+        self.compile(source, &tmp_dir_path, release, cancel)?;
+        if release {
+            self.check_bytecode(source, &tmp_dir_path, "pyo")?;
+            self.rename_optimized_bytecode(&tmp_dir_path)?;
+        } else {
+            self.check_bytecode(source, &tmp_dir_path, "pyc")?;
+        }
         self.delete_all_sources(&tmp_dir_path)?;
 
         create_dir_all(destination)?;
@@ -75,6 +133,129 @@ impl PythonBuilder {
         Ok(())
     }
 
+    fn check_imports(
+        &self, source: &PathBuf, build_info: &BuildInfo,
+    ) -> Result<()> {
+        let mut index = ModuleIndex::default();
+        for root in self.configs.game_sources.list_python_root_modules()? {
+            index.add_root(Path::new(&root), "")?;
+        }
+        if index.is_empty() {
+            report::message(
+                "No game Python sources found, skipping import validation",
+            );
+            return Ok(());
+        }
+        index.add_root(source, MODS_PACKAGE)?;
+        // Written next to the scripts once the imports are checked
+        index.insert(&format!("{MODS_PACKAGE}.{}", build_info.module_name()));
+
+        let diagnostics =
+            Diagnostics(check_imports(source, &display_root(source), &index)?);
+        report::diagnostics("python", &diagnostics);
+
+        if diagnostics.has_errors() {
+            return Err(Error::ImportError(diagnostics));
+        }
+
+        Ok(())
+    }
+
+    // Left by running the scripts locally with Python 3, only the bytecode
+    // compileall produces is checked and shipped
+    fn remove_pycache(&self, directory: &Path) -> Result<()> {
+        let pattern = directory.join("**").join("__pycache__");
+        let entries = glob(&pattern.to_string_lossy())?
+            .collect::<result::Result<Vec<_>, _>>()?;
+
+        for entry in entries {
+            remove_dir_all(entry).map_err(Error::WriteFilesError)?;
+        }
+
+        Ok(())
+    }
+
+    // The .pyc header embeds the source mtime, the copies are all dated
+    // from SOURCE_DATE_EPOCH to get the same bytecode on every build
+    fn stamp_sources(&self, directory: &Path) -> Result<()> {
+        let timestamp = u64::try_from(source_date_epoch()).unwrap_or_default();
+        let modified = UNIX_EPOCH + Duration::from_secs(timestamp);
+        let pattern = directory.join("**").join("*.py");
+
+        for entry in glob(&pattern.to_string_lossy())? {
+            File::options()
+                .write(true)
+                .open(entry?)
+                .and_then(|file| file.set_modified(modified))
+                .map_err(Error::WriteFilesError)?;
+        }
+
+        Ok(())
+    }
+
+    fn compile(
+        &self, source: &PathBuf, directory: &PathBuf, release: bool,
+        cancel: &AtomicBool,
+    ) -> Result<()> {
+        let output = self.conda_environment().compile_all(
+            directory,
+            release,
+            SCRIPTS_DISPLAY_PATH,
+            cancel,
+        )?;
+        let content = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let diagnostics = Diagnostics(parse_compile_output(
+            &content,
+            Path::new(SCRIPTS_DISPLAY_PATH),
+            &display_root(source),
+        ));
+
+        report::diagnostics("python", &diagnostics);
+
+        if diagnostics.has_errors() {
+            return Err(Error::CompileError(diagnostics));
+        }
+        if !output.status.success() {
+            return Err(Error::CompileOutputError(content));
+        }
+
+        Ok(())
+    }
+
+    fn check_bytecode(
+        &self, source: &Path, directory: &Path, extension: &str,
+    ) -> Result<()> {
+        let diagnostics = Diagnostics(check_bytecode(
+            directory,
+            &display_root(source),
+            extension,
+        )?);
+
+        if diagnostics.has_errors() {
+            return Err(Error::BytecodeError(diagnostics));
+        }
+
+        Ok(())
+    }
+
+    // The game only imports .pyc, the optimized bytecode has the same format
+    fn rename_optimized_bytecode(&self, directory: &Path) -> Result<()> {
+        let pattern = directory.join("**").join("*.pyo");
+
+        for entry in glob(&pattern.to_string_lossy())? {
+            let file = entry?;
+            rename(&file, file.with_extension("pyc"))
+                .map_err(Error::WriteFilesError)?;
+        }
+
+        Ok(())
+    }
+
     fn prepare_tmp_directory(
         &self,
     ) -> Result<(impl FnOnce() -> io::Result<()>, PathBuf)> {
@@ -101,3 +282,178 @@ impl PythonBuilder {
         Ok(())
     }
 }
+
+// Diagnostics show paths from the mod folder, like scripts/mod_example.py
+fn display_root(source: &Path) -> PathBuf {
+    source
+        .file_name()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| source.to_path_buf())
+}
+
+fn relative_source_path(
+    file: &str, directory: &Path, display_root: &Path,
+) -> PathBuf {
+    match Path::new(file).strip_prefix(directory) {
+        | Ok(relative) => display_root.join(relative),
+        | Err(_) => PathBuf::from(file),
+    }
+}
+
+// compileall prints the traceback of each failing file:
+//   File "<path>", line <n>
+//     <code>
+//         ^
+// SyntaxError: <message>
+// Compile warnings come from the warnings module as
+// <path>:<n>: SyntaxWarning: <message>
+fn parse_compile_output(
+    content: &str, directory: &Path, display_root: &Path,
+) -> Vec<Diagnostic> {
+    let location = Regex::new(r#"^\s*File "(.+)", line (\d+)"#).unwrap();
+    let exception =
+        Regex::new(r"^(\w+(?:Error|Exception))(?::\s*(.*))?$").unwrap();
+    let warning = Regex::new(r"^(.+?):(\d+): (\w*Warning): (.*)$").unwrap();
+
+    let mut diagnostics = vec![];
+    let mut current: Option<Diagnostic> = None;
+
+    for line in content.lines() {
+        if let Some(captures) = location.captures(line) {
+            current = Some(Diagnostic {
+                file: relative_source_path(
+                    &captures[1],
+                    directory,
+                    display_root,
+                ),
+                line: captures[2].parse().ok(),
+                column: None,
+                severity: Severity::Error,
+                message: String::new(),
+                snippet: vec![],
+            });
+            continue;
+        }
+
+        if let Some(mut diagnostic) = current.take() {
+            match exception.captures(line.trim()) {
+                | Some(captures) => {
+                    diagnostic.message = match captures.get(2) {
+                        | Some(message) => {
+                            format!("{} ({})", message.as_str(), &captures[1])
+                        },
+                        | None => captures[1].to_string(),
+                    };
+                    diagnostics.push(diagnostic);
+                },
+                | None => {
+                    diagnostic.snippet.push(
+                        line.strip_prefix("    ").unwrap_or(line).to_string(),
+                    );
+                    current = Some(diagnostic);
+                },
+            }
+            continue;
+        }
+
+        if let Some(captures) = warning.captures(line) {
+            diagnostics.push(Diagnostic {
+                file: relative_source_path(
+                    &captures[1],
+                    directory,
+                    display_root,
+                ),
+                line: captures[2].parse().ok(),
+                column: None,
+                severity: Severity::Warning,
+                message: format!("{} ({})", &captures[4], &captures[3]),
+                snippet: vec![],
+            });
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::mock_configs;
+    use std::fs::metadata;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parse_compileall_syntax_errors() {
+        let output = "Listing /tmp/build ...
+Compiling /tmp/build/mod_test.py ...
+  File \"/tmp/build/mod_test.py\", line 3
+    print 'hello
+               ^
+SyntaxError: EOL while scanning string literal
+
+Compiling /tmp/build/my_mod/__init__.py ...
+/tmp/build/my_mod/__init__.py:1: SyntaxWarning: import * only allowed at module level
+";
+
+        let diagnostics = parse_compile_output(
+            output,
+            Path::new("/tmp/build"),
+            Path::new("scripts"),
+        );
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "scripts/mod_test.py:3: error: EOL while scanning string literal (SyntaxError)
+    print 'hello
+               ^"
+        );
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "scripts/my_mod/__init__.py:1: warning: import * only allowed at module level (SyntaxWarning)"
+        );
+    }
+
+    #[test]
+    fn remove_local_pycache() {
+        let tmp_dir = tempdir().unwrap();
+        let home = tmp_dir.path().join("home");
+        let directory = tmp_dir.path().join("scripts");
+        create_dir_all(directory.join("__pycache__")).unwrap();
+        create_dir_all(directory.join("my_mod/__pycache__")).unwrap();
+        write(directory.join("mod_test.py"), "").unwrap();
+        write(
+            directory.join("my_mod/__pycache__/helper.cpython-312.pyc"),
+            "",
+        )
+        .unwrap();
+
+        let builder = PythonBuilder::new(Arc::new(mock_configs(&home)));
+        builder.remove_pycache(&directory).unwrap();
+
+        assert!(directory.join("mod_test.py").exists());
+        assert!(!directory.join("__pycache__").exists());
+        assert!(!directory.join("my_mod/__pycache__").exists());
+    }
+
+    #[test]
+    fn stamp_sources_with_a_fixed_date() {
+        let tmp_dir = tempdir().unwrap();
+        let home = tmp_dir.path().join("home");
+        let directory = tmp_dir.path().join("scripts");
+        create_dir_all(directory.join("my_mod")).unwrap();
+        write(directory.join("mod_test.py"), "").unwrap();
+        write(directory.join("my_mod/__init__.py"), "").unwrap();
+
+        let builder = PythonBuilder::new(Arc::new(mock_configs(&home)));
+        builder.stamp_sources(&directory).unwrap();
+
+        let expected = UNIX_EPOCH
+            + Duration::from_secs(source_date_epoch().try_into().unwrap());
+        for file in ["mod_test.py", "my_mod/__init__.py"] {
+            let modified = metadata(directory.join(file)).unwrap().modified();
+            assert_eq!(modified.unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::builder::diagnostic::{Diagnostic, Diagnostics};
+use serde_derive::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StageStatus {
+    Built,
+    Fresh,
+    Skipped,
+    Failed,
+    Cancelled,
+}
+
+// One JSON line is printed per event with --message-format json
+#[derive(Debug, Serialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+pub enum Event<'a> {
+    StageStarted {
+        stage: &'a str,
+    },
+    StageFinished {
+        stage: &'a str,
+        status: StageStatus,
+        duration_ms: u128,
+    },
+    Diagnostic {
+        stage: &'a str,
+        diagnostic: &'a Diagnostic,
+    },
+    Message {
+        message: &'a str,
+    },
+    BuildFinished {
+        archive: &'a str,
+        size: u64,
+        duration_ms: u128,
+    },
+    BuildFailed {
+        message: &'a str,
+    },
+    MemberFinished {
+        member: &'a str,
+        path: &'a str,
+        success: bool,
+        message: Option<&'a str>,
+    },
+}
+
+pub fn set_json_output(value: bool) {
+    JSON_OUTPUT.store(value, Ordering::Relaxed);
+}
+
+pub fn is_json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+pub fn emit(event: Event) {
+    if !is_json_output() {
+        return;
+    }
+
+    match serde_json::to_string(&event) {
+        | Ok(line) => println!("{line}"),
+        | Err(e) => eprintln!("Unable to serialize build event: {e}"),
+    }
+}
+
+pub fn message(message: &str) {
+    if is_json_output() {
+        emit(Event::Message { message });
+    } else {
+        println!("{message}");
+    }
+}
+
+// Errors are reported by the failing stage, only warnings are printed here
+pub fn diagnostics(stage: &str, diagnostics: &Diagnostics) {
+    if is_json_output() {
+        for diagnostic in &diagnostics.0 {
+            emit(Event::Diagnostic { stage, diagnostic });
+        }
+    } else {
+        for warning in diagnostics.warnings() {
+            println!("{warning}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::diagnostic::Severity;
+    use std::path::PathBuf;
+
+    #[test]
+    fn serialize_events_as_json_lines() {
+        let finished = Event::StageFinished {
+            stage: "python",
+            status: StageStatus::Built,
+            duration_ms: 12,
+        };
+        assert_eq!(
+            serde_json::to_string(&finished).unwrap(),
+            r#"{"reason":"stage-finished","stage":"python","status":"built","duration_ms":12}"#
+        );
+
+        let diagnostic = Diagnostic {
+            file: PathBuf::from("scripts/mod_test.py"),
+            line: Some(3),
+            column: None,
+            severity: Severity::Error,
+            message: "invalid syntax (SyntaxError)".to_string(),
+            snippet: vec![],
+        };
+        assert_eq!(
+            serde_json::to_string(&Event::Diagnostic {
+                stage: "python",
+                diagnostic: &diagnostic
+            })
+            .unwrap(),
+            r#"{"reason":"diagnostic","stage":"python","diagnostic":{"file":"scripts/mod_test.py","line":3,"column":null,"severity":"error","message":"invalid syntax (SyntaxError)","snippet":[]}}"#
+        );
+    }
+}
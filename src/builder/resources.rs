@@ -0,0 +1,156 @@
+use crate::config::mod_conf::ResourceMapping;
+use fs_extra::dir::get_dir_content;
+use glob::{MatchOptions, Pattern, PatternError};
+use std::fs::{copy, create_dir_all};
+use std::path::PathBuf;
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Resource source {0:?} does not exist")]
+    MissingSourceError(PathBuf),
+
+    #[error("Invalid resource pattern\n{0}")]
+    PatternError(#[from] PatternError),
+
+    #[error("Unable to list resource files\n{0}")]
+    ListError(#[from] fs_extra::error::Error),
+
+    #[error("Unable to copy resource files\n{0}")]
+    CopyError(#[from] io::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+pub struct ResourcesBuilder {
+    mappings: Vec<ResourceMapping>,
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Pattern>> {
+    Ok(patterns
+        .iter()
+        .map(|pattern| Pattern::new(pattern))
+        .collect::<result::Result<Vec<_>, _>>()?)
+}
+
+fn list_files(source: &PathBuf) -> Result<Vec<(PathBuf, String)>> {
+    if source.is_file() {
+        let file_name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        return Ok(vec![(source.clone(), file_name)]);
+    }
+
+    let files = get_dir_content(source)?
+        .files
+        .into_iter()
+        .map(PathBuf::from)
+        .filter_map(|file| {
+            let relative_path = file
+                .strip_prefix(source)
+                .ok()?
+                .components()
+                .map(|component| {
+                    component.as_os_str().to_string_lossy().to_string()
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            Some((file, relative_path))
+        })
+        .collect();
+
+    Ok(files)
+}
+
+impl ResourcesBuilder {
+    pub fn new(mappings: Vec<ResourceMapping>) -> Self {
+        Self { mappings }
+    }
+
+    pub fn build(
+        &self, mod_path: &PathBuf, destination: &PathBuf,
+    ) -> Result<()> {
+        for mapping in &self.mappings {
+            let source = mod_path.join(&mapping.source);
+            if !source.exists() {
+                return Err(Error::MissingSourceError(source));
+            }
+
+            let include = compile_patterns(&mapping.include)?;
+            let exclude = compile_patterns(&mapping.exclude)?;
+            let mapping_destination = destination.join(&mapping.destination);
+
+            for (file, relative_path) in list_files(&source)? {
+                let is_included = include.is_empty()
+                    || include.iter().any(|pattern| {
+                        pattern.matches_with(&relative_path, MATCH_OPTIONS)
+                    });
+                let is_excluded = exclude.iter().any(|pattern| {
+                    pattern.matches_with(&relative_path, MATCH_OPTIONS)
+                });
+                if !is_included || is_excluded {
+                    continue;
+                }
+
+                let file_destination = mapping_destination.join(&relative_path);
+                if let Some(parent) = file_destination.parent() {
+                    create_dir_all(parent)?;
+                }
+                copy(&file, &file_destination)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn copy_resources_with_patterns() {
+        let tmp_dir = tempdir().unwrap();
+        let mod_path = tmp_dir.path().to_path_buf();
+        let destination = mod_path.join("target/resources/res");
+
+        create_dir_all(mod_path.join("res/text")).unwrap();
+        write(mod_path.join("res/text/readme.txt"), "hello").unwrap();
+        create_dir_all(mod_path.join("configs/sub")).unwrap();
+        write(mod_path.join("configs/main.xml"), "<root/>").unwrap();
+        write(mod_path.join("configs/sub/other.xml"), "<root/>").unwrap();
+        write(mod_path.join("configs/sub/draft.xml"), "<root/>").unwrap();
+        write(mod_path.join("configs/notes.md"), "notes").unwrap();
+
+        let builder = ResourcesBuilder::new(vec![
+            ResourceMapping {
+                source: PathBuf::from("res"),
+                ..Default::default()
+            },
+            ResourceMapping {
+                source: PathBuf::from("configs"),
+                destination: PathBuf::from("configs/my_mod"),
+                include: vec!["**/*.xml".to_string()],
+                exclude: vec!["**/draft.xml".to_string()],
+            },
+        ]);
+        builder.build(&mod_path, &destination).unwrap();
+
+        assert!(destination.join("text/readme.txt").exists());
+        assert!(destination.join("configs/my_mod/main.xml").exists());
+        assert!(destination.join("configs/my_mod/sub/other.xml").exists());
+        assert!(!destination.join("configs/my_mod/sub/draft.xml").exists());
+        assert!(!destination.join("configs/my_mod/notes.md").exists());
+
+        tmp_dir.close().unwrap();
+    }
+}
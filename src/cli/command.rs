@@ -1,8 +1,18 @@
 use crate::cli::commands::channel::ChannelCommand;
 use crate::cli::commands::export::ExportCommand;
+use crate::cli::commands::i18n::I18nCommand;
+use crate::cli::commands::import::ImportCommand;
+use crate::cli::commands::inspect::InspectCommand;
+use crate::cli::commands::install::InstallCommand;
 use crate::cli::commands::new::NewCommand;
+use crate::cli::commands::pack::PackCommand;
 use crate::cli::commands::pycharm::PycharmCommand;
-use clap::{ArgMatches, Command};
+use crate::cli::commands::verify::VerifyCommand;
+use crate::cli::commands::watch::WatchCommand;
+use crate::config;
+use crate::config::Configs;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::sync::Arc;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -14,12 +24,33 @@ pub enum Error {
 
     #[error("Error occurred during the command execution\n{0}")]
     CommandExecutionError(String),
+
+    #[error("wg-mod configs aren't loaded for this command")]
+    ConfigsNotLoaded,
+}
+
+// Toolchain loaded once per invocation and shared by the command
+#[derive(Default)]
+pub struct Context {
+    configs: Option<Arc<Configs>>,
+}
+
+impl Context {
+    pub fn load() -> Result<Self, config::Error> {
+        Ok(Self {
+            configs: Some(Arc::new(Configs::load()?)),
+        })
+    }
+
+    pub fn configs(&self) -> Result<Arc<Configs>, Error> {
+        self.configs.clone().ok_or(Error::ConfigsNotLoaded)
+    }
 }
 
 pub trait RunnableCommand {
     fn command() -> Command;
 
-    fn run(args: &ArgMatches) -> Result<(), Error>;
+    fn run(args: &ArgMatches, context: &Context) -> Result<(), Error>;
 }
 
 pub fn command() -> Command {
@@ -28,8 +59,22 @@ pub fn command() -> Command {
         .author("Gabriel Hamel <gabriel.hamel.pro@gmail.com>")
         .about("Provides cli tools for Wargaming games modding")
         .subcommand_required(true)
+        .arg(
+            Arg::new("non-interactive")
+                .long("non-interactive")
+                .help("Fail with an error instead of prompting for input")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
         .subcommand(NewCommand::command())
+        .subcommand(ImportCommand::command())
         .subcommand(ExportCommand::command())
+        .subcommand(WatchCommand::command())
+        .subcommand(InstallCommand::command())
+        .subcommand(VerifyCommand::command())
+        .subcommand(InspectCommand::command())
+        .subcommand(PackCommand::command())
+        .subcommand(I18nCommand::command())
         .subcommand(PycharmCommand::command())
         .subcommand(ChannelCommand::command())
 }
@@ -1,6 +1,5 @@
 use crate::cli::command;
-use crate::cli::command::RunnableCommand;
-use crate::config;
+use crate::cli::command::{Context, RunnableCommand};
 use crate::config::Configs;
 use crate::sdk::game_sources;
 use clap::{ArgMatches, Command};
@@ -8,9 +7,6 @@ use std::result;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("Failed to load modding tools\n{0}")]
-    ConfigsError(#[from] config::Error),
-
     #[error("Failed to use build tools\n{0}")]
     GameSourceError(#[from] game_sources::Error),
 }
@@ -19,17 +15,15 @@ type Result<T> = result::Result<T, Error>;
 
 pub struct ChannelCommand;
 
-fn channel() -> Result<()> {
-    let config = Configs::load()?;
-    let channel = config.game_sources.get_channel()?;
+fn channel(configs: &Configs) -> Result<()> {
+    let channel = configs.game_sources.get_channel()?;
 
     println!("Current WoT channel: {}", channel);
     Ok(())
 }
 
-fn switch_channel() -> Result<()> {
-    let config = Configs::load()?;
-    config.game_sources.prompt_channel()?;
+fn switch_channel(configs: &Configs) -> Result<()> {
+    configs.game_sources.prompt_channel()?;
 
     Ok(())
 }
@@ -47,9 +41,12 @@ impl RunnableCommand for ChannelCommand {
             )
     }
 
-    fn run(args: &ArgMatches) -> result::Result<(), command::Error> {
+    fn run(
+        args: &ArgMatches, context: &Context,
+    ) -> result::Result<(), command::Error> {
+        let configs = context.configs()?;
         if let Some(_) = args.subcommand_matches("switch") {
-            return match switch_channel() {
+            return match switch_channel(&configs) {
                 | Ok(()) => Ok(()),
                 | Err(e) => {
                     Err(command::Error::CommandExecutionError(e.to_string()))
@@ -57,7 +54,7 @@ impl RunnableCommand for ChannelCommand {
             };
         }
 
-        match channel() {
+        match channel(&configs) {
             | Ok(()) => Ok(()),
             | Err(e) => {
                 Err(command::Error::CommandExecutionError(e.to_string()))
@@ -1,41 +1,221 @@
 use crate::builder;
-use crate::builder::ModBuilder;
+use crate::builder::report::Event;
+use crate::builder::{report, BuildOptions, ModBuilder};
 use crate::cli::command;
-use crate::cli::command::RunnableCommand;
-use clap::{ArgMatches, Command};
+use crate::cli::command::{Context, RunnableCommand};
+use crate::cli::commands::install;
+use crate::cli::commands::install::install_archive;
+use crate::config::workspace;
+use crate::config::workspace::{select_members, Member, WORKSPACE_FILE};
+use crate::config::Configs;
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use std::path::PathBuf;
 use std::result;
+use std::sync::Arc;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Failed to use build tools\n{0}")]
     ModBuilderError(#[from] builder::Error),
+
+    #[error("{0}")]
+    InstallError(#[from] install::Error),
+
+    #[error("Invalid workspace\n{0}")]
+    WorkspaceError(#[from] workspace::Error),
+
+    #[error("{0} of {1} workspace member(s) failed")]
+    MembersFailedError(usize, usize),
 }
 
 type Result<T> = result::Result<T, Error>;
 
 pub struct ExportCommand;
 
-fn build() -> Result<()> {
+fn build(
+    options: BuildOptions, install: bool, configs: Arc<Configs>,
+) -> Result<()> {
     let mod_path = PathBuf::from(".");
-    let mod_builder = ModBuilder::new(mod_path)?;
-    mod_builder.build()?;
+    let mod_builder = ModBuilder::with_configs(mod_path, options, configs);
+    let archive_path = mod_builder.build()?;
+
+    if install {
+        install_archive(&archive_path)?;
+    }
+
+    Ok(())
+}
+
+fn build_member(
+    member: &Member, options: &BuildOptions, install: bool,
+    configs: &Arc<Configs>,
+) -> Result<PathBuf> {
+    let mod_builder = ModBuilder::with_configs(
+        member.path.clone(),
+        options.clone(),
+        configs.clone(),
+    );
+    let archive_path = mod_builder.build()?;
+
+    if install {
+        install_archive(&archive_path)?;
+    }
+
+    Ok(archive_path)
+}
+
+fn build_workspace(
+    options: BuildOptions, install: bool, packages: &[String],
+    configs: Arc<Configs>,
+) -> Result<()> {
+    let members = select_members(&PathBuf::from("."), packages)?;
+
+    let mut failures = 0;
+    for member in &members {
+        let name = member
+            .package_name()
+            .cloned()
+            .unwrap_or_else(|| member.path.display().to_string());
+        let path = member.path.display().to_string();
+        report::message(&format!("Building {name} ({path})"));
+
+        let result = build_member(member, &options, install, &configs);
+        let message = result.as_ref().err().map(|e| e.to_string());
+        if report::is_json_output() {
+            report::emit(Event::MemberFinished {
+                member: &name,
+                path: &path,
+                success: result.is_ok(),
+                message: message.as_deref(),
+            });
+        }
+
+        if let Some(message) = message {
+            failures += 1;
+            if !report::is_json_output() {
+                eprintln!("{name} failed\n{message}");
+            }
+        }
+    }
+
+    if !report::is_json_output() {
+        println!(
+            "Workspace built: {} succeeded, {} failed",
+            members.len() - failures,
+            failures
+        );
+    }
+
+    if failures > 0 {
+        return Err(Error::MembersFailedError(failures, members.len()));
+    }
 
     Ok(())
 }
 
+fn collect_options(args: &ArgMatches) -> BuildOptions {
+    BuildOptions {
+        main_class: args.get_one::<String>("main-class").cloned(),
+        clean: args.get_flag("clean"),
+        output: args.get_one::<String>("output").map(PathBuf::from),
+        release: args.get_flag("release"),
+    }
+}
+
 impl RunnableCommand for ExportCommand {
     fn command() -> Command {
         Command::new("export")
             .about("Assemble sources into a .wotmod")
             .long_about("Compile the local mod directory as a .wotmod file")
+            .arg(
+                Arg::new("main-class")
+                    .long("main-class")
+                    .value_name("CLASS")
+                    .help("Main ui class (from ui/src), saved into mod.json, not allowed with ui_targets"),
+            )
+            .arg(
+                Arg::new("clean")
+                    .long("clean")
+                    .help("Ignore the build cache and rebuild every stage")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .value_name("FILE")
+                    .help("Path of the produced .wotmod"),
+            )
+            .arg(
+                Arg::new("release")
+                    .long("release")
+                    .help("Strip asserts, docstrings and local paths from the bytecode")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("install")
+                    .long("install")
+                    .help("Deploy the built .wotmod into the game client")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("workspace")
+                    .long("workspace")
+                    .help(format!(
+                        "Build every member listed in {WORKSPACE_FILE}"
+                    ))
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("output"),
+            )
+            .arg(
+                Arg::new("package")
+                    .long("package")
+                    .short('p')
+                    .value_name("ID")
+                    .help(
+                        "Only build the workspace member with this package id",
+                    )
+                    .action(ArgAction::Append)
+                    .conflicts_with("output"),
+            )
+            .arg(
+                Arg::new("message-format")
+                    .long("message-format")
+                    .value_name("FORMAT")
+                    .value_parser(["human", "json"])
+                    .default_value("human")
+                    .help("Print build events as human text or JSON lines"),
+            )
     }
 
-    fn run(_: &ArgMatches) -> result::Result<(), command::Error> {
-        match build() {
+    fn run(
+        args: &ArgMatches, context: &Context,
+    ) -> result::Result<(), command::Error> {
+        report::set_json_output(
+            args.get_one::<String>("message-format")
+                .is_some_and(|format| format == "json"),
+        );
+
+        let options = collect_options(args);
+        let install = args.get_flag("install");
+        let packages = args
+            .get_many::<String>("package")
+            .map(|packages| packages.cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let configs = context.configs()?;
+        let result = if args.get_flag("workspace") || !packages.is_empty() {
+            build_workspace(options, install, &packages, configs)
+        } else {
+            build(options, install, configs)
+        };
+
+        match result {
             | Ok(()) => Ok(()),
             | Err(e) => {
-                Err(command::Error::CommandExecutionError(e.to_string()))
+                let message = e.to_string();
+                report::emit(Event::BuildFailed { message: &message });
+                Err(command::Error::CommandExecutionError(message))
             },
         }
     }
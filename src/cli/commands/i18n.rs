@@ -0,0 +1,85 @@
+use crate::cli::command;
+use crate::cli::command::{Context, RunnableCommand};
+use crate::config::mod_conf::ModConf;
+use crate::i18n::extract;
+use crate::i18n::extract::Catalog;
+use clap::{Arg, ArgMatches, Command};
+use convert_case::{Case, Casing};
+use std::fs::{create_dir_all, write};
+use std::path::PathBuf;
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to read or write files\n{0}")]
+    FileError(#[from] io::Error),
+
+    #[error("Failed to extract messages\n{0}")]
+    ExtractError(#[from] extract::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub struct I18nCommand;
+
+fn extract_messages(output: Option<PathBuf>) -> Result<()> {
+    let mod_path = PathBuf::from(".");
+    let output = match output {
+        | Some(output) => output,
+        | None => {
+            let mod_conf = ModConf::from_file(&mod_path.join("mod.json"))?;
+            let domain = mod_conf.name.to_case(Case::Snake);
+            mod_path.join("locales").join(format!("{domain}.pot"))
+        },
+    };
+
+    let mut catalog = Catalog::default();
+    catalog.scan_directory(&mod_path, &mod_path.join("scripts"))?;
+    catalog.scan_directory(&mod_path, &mod_path.join("ui").join("src"))?;
+
+    if let Some(parent) = output.parent() {
+        create_dir_all(parent)?;
+    }
+    write(&output, catalog.to_pot())?;
+    println!("Messages extracted into {}", output.display());
+
+    Ok(())
+}
+
+impl RunnableCommand for I18nCommand {
+    fn command() -> Command {
+        Command::new("i18n")
+            .about("Manage mod translations")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("extract")
+                    .about("Extract translatable messages into a .pot template")
+                    .long_about("Scan Python _() / i18n.makeString calls and AS3 \"#domain:key\" strings into a .pot template")
+                    .arg(
+                        Arg::new("output")
+                            .long("output")
+                            .short('o')
+                            .value_name("FILE")
+                            .help("Path of the produced .pot file"),
+                    ),
+            )
+    }
+
+    fn run(
+        args: &ArgMatches, _: &Context,
+    ) -> result::Result<(), command::Error> {
+        let result = match args.subcommand() {
+            | Some(("extract", args)) => extract_messages(
+                args.get_one::<String>("output").map(PathBuf::from),
+            ),
+            | _ => return Err(command::Error::CommandNotImplemented),
+        };
+
+        match result {
+            | Ok(()) => Ok(()),
+            | Err(e) => {
+                Err(command::Error::CommandExecutionError(e.to_string()))
+            },
+        }
+    }
+}
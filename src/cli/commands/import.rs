@@ -0,0 +1,57 @@
+use crate::cli::command;
+use crate::cli::command::{Context, RunnableCommand};
+use crate::new::import;
+use crate::new::import::{import_archive, print_report};
+use clap::{Arg, ArgMatches, Command};
+use std::path::PathBuf;
+use std::result;
+
+pub struct ImportCommand;
+
+fn import(archive_path: &PathBuf, directory: &PathBuf) -> import::Result<()> {
+    let report = import_archive(archive_path, directory)?;
+
+    print_report(&report)
+}
+
+impl RunnableCommand for ImportCommand {
+    fn command() -> Command {
+        Command::new("import")
+            .about("Create a mod project from an existing .wotmod")
+            .long_about("Extract a .wotmod into a buildable mod project, meta.xml becomes mod.json and compiled files without sources are reported")
+            .arg(
+                Arg::new("archive")
+                    .value_name("ARCHIVE")
+                    .required(true)
+                    .help("Path of the .wotmod to import"),
+            )
+            .arg(
+                Arg::new("directory")
+                    .long("directory")
+                    .short('d')
+                    .value_name("DIR")
+                    .default_value(".")
+                    .help("Directory in which the project is created"),
+            )
+    }
+
+    fn run(
+        args: &ArgMatches, _: &Context,
+    ) -> result::Result<(), command::Error> {
+        let archive_path = args
+            .get_one::<String>("archive")
+            .map(PathBuf::from)
+            .unwrap_or_default();
+        let directory = args
+            .get_one::<String>("directory")
+            .map(PathBuf::from)
+            .unwrap_or_default();
+
+        match import(&archive_path, &directory) {
+            | Ok(()) => Ok(()),
+            | Err(e) => {
+                Err(command::Error::CommandExecutionError(e.to_string()))
+            },
+        }
+    }
+}
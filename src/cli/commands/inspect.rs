@@ -0,0 +1,73 @@
+use crate::cli::command;
+use crate::cli::command::{Context, RunnableCommand};
+use crate::cli::commands::install;
+use crate::cli::commands::install::default_archive_path;
+use crate::wotmod;
+use crate::wotmod::inspect::inspect;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::path::PathBuf;
+use std::result;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    ArchivePathError(#[from] install::Error),
+
+    #[error("Unable to read the archive\n{0}")]
+    ArchiveError(#[from] wotmod::Error),
+
+    #[error("Unable to serialize the summary\n{0}")]
+    SerializeError(#[from] serde_json::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub struct InspectCommand;
+
+fn inspect_archive(archive_path: Option<PathBuf>, json: bool) -> Result<()> {
+    let archive_path = match archive_path {
+        | Some(archive_path) => archive_path,
+        | None => default_archive_path()?,
+    };
+
+    let inspection = inspect(&archive_path)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&inspection)?);
+    } else {
+        println!("{inspection}");
+    }
+
+    Ok(())
+}
+
+impl RunnableCommand for InspectCommand {
+    fn command() -> Command {
+        Command::new("inspect")
+            .about("Summarize the content of a .wotmod")
+            .long_about("Print the meta.xml fields, the file tree with sizes, counts per file kind and compression methods of a .wotmod")
+            .arg(
+                Arg::new("archive")
+                    .value_name("ARCHIVE")
+                    .help("Path of the .wotmod to inspect, defaults to the last export"),
+            )
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .help("Print the summary as JSON")
+                    .action(ArgAction::SetTrue),
+            )
+    }
+
+    fn run(
+        args: &ArgMatches, _: &Context,
+    ) -> result::Result<(), command::Error> {
+        let archive_path = args.get_one::<String>("archive").map(PathBuf::from);
+
+        match inspect_archive(archive_path, args.get_flag("json")) {
+            | Ok(()) => Ok(()),
+            | Err(e) => {
+                Err(command::Error::CommandExecutionError(e.to_string()))
+            },
+        }
+    }
+}
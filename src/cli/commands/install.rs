@@ -0,0 +1,95 @@
+use crate::builder::report;
+use crate::cli::command;
+use crate::cli::command::{Context, RunnableCommand};
+use crate::config::mod_conf::ModConf;
+use crate::config::settings::load_settings;
+use crate::config::{mod_conf, settings};
+use crate::sdk::game_client;
+use crate::sdk::game_client::GameClient;
+use clap::{Arg, ArgMatches, Command};
+use std::path::PathBuf;
+use std::result;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to find the game client\n{0}")]
+    SettingsError(#[from] settings::Error),
+
+    #[error("Failed to install into the game client\n{0}")]
+    GameClientError(#[from] game_client::Error),
+
+    #[error("The archive {0:?} doesn't exist, run \"wg-mod export\" first")]
+    MissingArchiveError(PathBuf),
+
+    #[error("Unable to read mod.json\n{0}")]
+    ModConfFileError(#[from] std::io::Error),
+
+    #[error("Unable to compute the archive name\n{0}")]
+    ModConfError(#[from] mod_conf::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub struct InstallCommand;
+
+pub fn default_archive_path() -> Result<PathBuf> {
+    let mod_conf = ModConf::from_file(&PathBuf::from("mod.json"))?;
+
+    Ok(PathBuf::from("target").join(mod_conf.archive_file_name()?))
+}
+
+fn install(archive_path: Option<PathBuf>) -> Result<()> {
+    let archive_path = match archive_path {
+        | Some(archive_path) => archive_path,
+        | None => default_archive_path()?,
+    };
+
+    install_archive(&archive_path)
+}
+
+pub fn install_archive(archive_path: &PathBuf) -> Result<()> {
+    if !archive_path.exists() {
+        return Err(Error::MissingArchiveError(archive_path.clone()));
+    }
+
+    let mut settings = load_settings()?;
+    let game_client_path = settings.require_game_client_path()?;
+    let game_client = GameClient::from(&game_client_path);
+
+    let install_report = game_client.install_mod(archive_path)?;
+    for replaced in &install_report.replaced {
+        report::message(&format!("Replaced: {}", replaced.display()));
+    }
+    report::message(&format!(
+        "Installed: {}",
+        install_report.installed.display()
+    ));
+
+    Ok(())
+}
+
+impl RunnableCommand for InstallCommand {
+    fn command() -> Command {
+        Command::new("install")
+            .about("Deploy a .wotmod into the game client")
+            .long_about("Copy a .wotmod into the mods folder of the configured game client, replacing older builds of the same package")
+            .arg(
+                Arg::new("archive")
+                    .value_name("ARCHIVE")
+                    .help("Path of the .wotmod to install, defaults to the last export"),
+            )
+    }
+
+    fn run(
+        args: &ArgMatches, _: &Context,
+    ) -> result::Result<(), command::Error> {
+        let archive_path = args.get_one::<String>("archive").map(PathBuf::from);
+
+        match install(archive_path) {
+            | Ok(()) => Ok(()),
+            | Err(e) => {
+                Err(command::Error::CommandExecutionError(e.to_string()))
+            },
+        }
+    }
+}
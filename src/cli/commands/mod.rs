@@ -1,4 +1,11 @@
 pub mod channel;
 pub mod export;
+pub mod i18n;
+pub mod import;
+pub mod inspect;
+pub mod install;
 pub mod new;
+pub mod pack;
 pub mod pycharm;
+pub mod verify;
+pub mod watch;
@@ -1,5 +1,6 @@
 use crate::cli::command;
-use crate::cli::command::RunnableCommand;
+use crate::cli::command::{Context, RunnableCommand};
+use crate::config::mod_conf::{PACKAGE_NAME_PATTERN, VERSION_PATTERN};
 use crate::new::{template::create_mod_files, NewArgs};
 use crate::utils::pattern_validator::PatternValidator;
 use clap::{ArgMatches, Command};
@@ -21,7 +22,7 @@ type Result<T> = result::Result<T, Error>;
 
 fn prompt_version() -> Result<String> {
     let validator = PatternValidator::new(
-        r"^(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)$",
+        VERSION_PATTERN,
         "Your mod version must respect the semantic versioning",
     )?;
 
@@ -53,7 +54,7 @@ fn prompt_description() -> Result<String> {
 
 fn prompt_package_name(name: &String) -> Result<String> {
     let validator = PatternValidator::new(
-        r"^([a-z]{1}[a-z-\d_]*\.)+[a-z][a-z-\d_]*$",
+        PACKAGE_NAME_PATTERN,
         "Your package name must be formated like this <prefix>.<dotted-namespace>.<mod-name>, only lower case allowed",
     )?;
 
@@ -96,7 +97,7 @@ impl RunnableCommand for NewCommand {
             .long_about("Create a directory with all default configs files and mod entrypoints")
     }
 
-    fn run(_: &ArgMatches) -> result::Result<(), command::Error> {
+    fn run(_: &ArgMatches, _: &Context) -> result::Result<(), command::Error> {
         match collect_args() {
             | Ok(args) => match create_mod_files(args) {
                 | Ok(()) => Ok(()),
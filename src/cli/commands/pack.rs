@@ -0,0 +1,190 @@
+use crate::cli::command;
+use crate::cli::command::{Context, RunnableCommand};
+use crate::config::mod_conf;
+use crate::config::settings::load_settings;
+use crate::config::workspace::{select_members, WORKSPACE_FILE};
+use crate::config::{settings, workspace};
+use crate::sdk::game_client;
+use crate::sdk::game_client::GameClient;
+use crate::wotmod::pack;
+use crate::wotmod::pack::{pack, PackOptions, PackedMod};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::path::PathBuf;
+use std::result;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Failed to pack mods\n{0}")]
+    PackError(#[from] pack::Error),
+
+    #[error("Invalid workspace\n{0}")]
+    WorkspaceError(#[from] workspace::Error),
+
+    #[error("Unable to read mod.json of {0:?}")]
+    ModConfFileError(PathBuf),
+
+    #[error("Unable to compute the archive name\n{0}")]
+    ModConfError(#[from] mod_conf::Error),
+
+    #[error("The archive {0:?} doesn't exist, run \"wg-mod export\" first")]
+    MissingArchiveError(PathBuf),
+
+    #[error("Unable to find the game client, pass --game-version\n{0}")]
+    SettingsError(#[from] settings::Error),
+
+    #[error("Unable to read the game client version\n{0}")]
+    GameClientError(#[from] game_client::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+const LICENSE_FILES: [&str; 4] =
+    ["LICENSE", "LICENSE.md", "LICENSE.txt", "COPYING"];
+
+pub struct PackCommand;
+
+fn workspace_mods(packages: &[String]) -> Result<Vec<PackedMod>> {
+    let mut mods = vec![];
+
+    for member in select_members(&PathBuf::from("."), packages)? {
+        let mod_conf = member
+            .mod_conf
+            .ok_or(Error::ModConfFileError(member.path.clone()))?;
+        let archive = member
+            .path
+            .join("target")
+            .join(mod_conf.archive_file_name()?);
+        if !archive.exists() {
+            return Err(Error::MissingArchiveError(archive));
+        }
+
+        let licenses = LICENSE_FILES
+            .iter()
+            .map(|name| member.path.join(name))
+            .filter(|path| path.is_file())
+            .collect();
+
+        mods.push(PackedMod { archive, licenses });
+    }
+
+    Ok(mods)
+}
+
+fn game_version(game_version: Option<String>) -> Result<String> {
+    if let Some(game_version) = game_version {
+        return Ok(game_version);
+    }
+
+    let mut settings = load_settings()?;
+    let game_client_path = settings.require_game_client_path()?;
+
+    Ok(GameClient::from(&game_client_path).version()?)
+}
+
+fn pack_mods(args: &ArgMatches) -> Result<()> {
+    let packages = args
+        .get_many::<String>("package")
+        .map(|packages| packages.cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let mut mods = vec![];
+    if args.get_flag("workspace") || !packages.is_empty() {
+        mods.extend(workspace_mods(&packages)?);
+    }
+    if let Some(archives) = args.get_many::<String>("archives") {
+        mods.extend(archives.map(|archive| PackedMod {
+            archive: PathBuf::from(archive),
+            licenses: vec![],
+        }));
+    }
+
+    let name = args.get_one::<String>("name").cloned().unwrap_or_default();
+    let output = args
+        .get_one::<String>("output")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(format!("{name}.zip")));
+    let options = PackOptions {
+        name,
+        game_version: game_version(
+            args.get_one::<String>("game-version").cloned(),
+        )?,
+        licenses: args
+            .get_many::<String>("license")
+            .map(|licenses| licenses.map(PathBuf::from).collect())
+            .unwrap_or_default(),
+    };
+
+    let manifest = pack(&mods, &options, &output)?;
+    for packed_mod in &manifest.mods {
+        println!("Packed {} {}", packed_mod.id, packed_mod.version);
+    }
+    println!("Pack written to {}", output.display());
+
+    Ok(())
+}
+
+impl RunnableCommand for PackCommand {
+    fn command() -> Command {
+        Command::new("pack")
+            .about("Bundle several .wotmod into a zip for players")
+            .long_about("Collect .wotmod files into a zip with the mods/<game_version>/ layout, a README, license files and a manifest with SHA-256 checksums")
+            .arg(
+                Arg::new("archives")
+                    .value_name("ARCHIVE")
+                    .num_args(0..)
+                    .help("Paths of the .wotmod to bundle"),
+            )
+            .arg(
+                Arg::new("workspace")
+                    .long("workspace")
+                    .help(format!("Bundle the last export of every member listed in {WORKSPACE_FILE}"))
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("package")
+                    .long("package")
+                    .short('p')
+                    .value_name("ID")
+                    .help("Only bundle the workspace member with this package id")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("name")
+                    .long("name")
+                    .value_name("NAME")
+                    .default_value("modpack")
+                    .help("Name of the pack"),
+            )
+            .arg(
+                Arg::new("game-version")
+                    .long("game-version")
+                    .value_name("VERSION")
+                    .help("Game version of the mods/ folder, defaults to the configured game client"),
+            )
+            .arg(
+                Arg::new("license")
+                    .long("license")
+                    .value_name("FILE")
+                    .help("License file added at the root of the pack")
+                    .action(ArgAction::Append),
+            )
+            .arg(
+                Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .value_name("FILE")
+                    .help("Path of the produced zip, defaults to <name>.zip"),
+            )
+    }
+
+    fn run(
+        args: &ArgMatches, _: &Context,
+    ) -> result::Result<(), command::Error> {
+        match pack_mods(args) {
+            | Ok(()) => Ok(()),
+            | Err(e) => {
+                Err(command::Error::CommandExecutionError(e.to_string()))
+            },
+        }
+    }
+}
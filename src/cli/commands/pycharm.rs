@@ -1,6 +1,5 @@
 use crate::cli::command;
-use crate::cli::command::RunnableCommand;
-use crate::config;
+use crate::cli::command::{Context, RunnableCommand};
 use crate::config::Configs;
 use crate::sdk::game_sources;
 use clap::{ArgMatches, Command};
@@ -8,9 +7,6 @@ use std::result;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("Failed to load modding tools\n{0}")]
-    ConfigsError(#[from] config::Error),
-
     #[error("Failed to use build tools\n{0}")]
     GameSourceError(#[from] game_sources::Error),
 }
@@ -19,9 +15,9 @@ type Result<T> = result::Result<T, Error>;
 
 pub struct PycharmCommand;
 
-fn pycharm() -> Result<()> {
-    let config = Configs::load()?;
-    let python_root_modules = config.game_sources.list_python_root_modules()?;
+fn pycharm(configs: &Configs) -> Result<()> {
+    let python_root_modules =
+        configs.game_sources.list_python_root_modules()?;
 
     println!("Resolve WoT imports:
 1. Go in your PyCharm project settings
@@ -43,8 +39,11 @@ impl RunnableCommand for PycharmCommand {
             .about("Help to configure your local PyCharm IDE")
     }
 
-    fn run(_: &ArgMatches) -> result::Result<(), command::Error> {
-        match pycharm() {
+    fn run(
+        _: &ArgMatches, context: &Context,
+    ) -> result::Result<(), command::Error> {
+        let configs = context.configs()?;
+        match pycharm(&configs) {
             | Ok(()) => Ok(()),
             | Err(e) => {
                 Err(command::Error::CommandExecutionError(e.to_string()))
@@ -0,0 +1,81 @@
+use crate::cli::command;
+use crate::cli::command::{Context, RunnableCommand};
+use crate::cli::commands::install;
+use crate::cli::commands::install::default_archive_path;
+use crate::wotmod;
+use crate::wotmod::verify::{verify, VerifyOptions};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::path::PathBuf;
+use std::result;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    ArchivePathError(#[from] install::Error),
+
+    #[error("Unable to read the archive\n{0}")]
+    ArchiveError(#[from] wotmod::Error),
+
+    #[error("{0} problem(s) found in {1:?}")]
+    InvalidArchiveError(usize, PathBuf),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub struct VerifyCommand;
+
+fn verify_archive(
+    archive_path: Option<PathBuf>, options: VerifyOptions,
+) -> Result<()> {
+    let archive_path = match archive_path {
+        | Some(archive_path) => archive_path,
+        | None => default_archive_path()?,
+    };
+
+    let issues = verify(&archive_path, &options)?;
+    if !issues.is_empty() {
+        for issue in &issues {
+            println!("{issue}");
+        }
+        return Err(Error::InvalidArchiveError(issues.len(), archive_path));
+    }
+
+    println!("{} is valid", archive_path.display());
+
+    Ok(())
+}
+
+impl RunnableCommand for VerifyCommand {
+    fn command() -> Command {
+        Command::new("verify")
+            .about("Check a .wotmod against the game loader rules")
+            .long_about("Check that a .wotmod has stored entries, a valid meta.xml, files under res/ only and a mod_*.pyc Python entry point")
+            .arg(
+                Arg::new("archive")
+                    .value_name("ARCHIVE")
+                    .help("Path of the .wotmod to check, defaults to the last export"),
+            )
+            .arg(
+                Arg::new("allow-sources")
+                    .long("allow-sources")
+                    .help("Accept .py sources in the archive")
+                    .action(ArgAction::SetTrue),
+            )
+    }
+
+    fn run(
+        args: &ArgMatches, _: &Context,
+    ) -> result::Result<(), command::Error> {
+        let archive_path = args.get_one::<String>("archive").map(PathBuf::from);
+        let options = VerifyOptions {
+            allow_sources: args.get_flag("allow-sources"),
+        };
+
+        match verify_archive(archive_path, options) {
+            | Ok(()) => Ok(()),
+            | Err(e) => {
+                Err(command::Error::CommandExecutionError(e.to_string()))
+            },
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::builder;
+use crate::builder::{BuildOptions, ModBuilder};
+use crate::cli::command;
+use crate::cli::command::{Context, RunnableCommand};
+use crate::config::Configs;
+use clap::{ArgMatches, Command};
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, notify};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::result;
+use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Failed to use build tools\n{0}")]
+    ModBuilderError(#[from] builder::Error),
+
+    #[error("Unable to watch the mod directory\n{0}")]
+    WatchError(#[from] notify::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub struct WatchCommand;
+
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+fn rebuild(mod_builder: &ModBuilder) {
+    if let Err(e) = mod_builder.build() {
+        eprintln!("{}", e);
+    }
+}
+
+// Content of the sources the build rewrites, as the last build left them
+fn snapshot(
+    mod_builder: &ModBuilder, root: &Path,
+) -> HashMap<PathBuf, Vec<u8>> {
+    mod_builder
+        .rewritten_paths()
+        .into_iter()
+        .filter_map(|path| {
+            let content = fs::read(root.join(&path)).ok()?;
+            Some((path, content))
+        })
+        .collect()
+}
+
+fn watch(configs: Arc<Configs>) -> Result<()> {
+    let mod_path = PathBuf::from(".");
+    let mod_builder = ModBuilder::with_configs(
+        mod_path.clone(),
+        BuildOptions::default(),
+        configs,
+    );
+
+    let (sender, receiver) = channel();
+    let mut debouncer = new_debouncer(DEBOUNCE, sender)?;
+
+    // The whole folder is watched, so directories created later and a
+    // mod.json replaced by an editor's atomic save are seen too
+    let root = mod_path.canonicalize().map_err(notify::Error::io)?;
+    debouncer.watcher().watch(&root, RecursiveMode::Recursive)?;
+
+    rebuild(&mod_builder);
+    let mut rewritten = snapshot(&mod_builder, &root);
+
+    println!("Watching for changes, press Ctrl+C to stop");
+
+    for result in receiver {
+        match result {
+            | Ok(events) => {
+                // A rewritten source still holding what the build wrote is
+                // the build's own change, anything else is a user edit
+                let changed = events
+                    .iter()
+                    .filter_map(|event| event.path.strip_prefix(&root).ok())
+                    .filter(|path| mod_builder.is_watched(path))
+                    .filter(|path| {
+                        rewritten.get(*path)
+                            != fs::read(root.join(path)).ok().as_ref()
+                    })
+                    .collect::<Vec<_>>();
+                if changed.is_empty() {
+                    continue;
+                }
+
+                for path in changed {
+                    println!("Changed: {}", path.display());
+                }
+                rebuild(&mod_builder);
+                rewritten = snapshot(&mod_builder, &root);
+            },
+            | Err(e) => eprintln!("Watch error: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+impl RunnableCommand for WatchCommand {
+    fn command() -> Command {
+        Command::new("watch")
+            .about("Rebuild the .wotmod when sources change")
+            .long_about("Watch the local mod directory and rebuild the stages affected by each change")
+    }
+
+    fn run(
+        _: &ArgMatches, context: &Context,
+    ) -> result::Result<(), command::Error> {
+        match watch(context.configs()?) {
+            | Ok(()) => Ok(()),
+            | Err(e) => {
+                Err(command::Error::CommandExecutionError(e.to_string()))
+            },
+        }
+    }
+}
@@ -2,20 +2,74 @@ mod command;
 pub mod commands;
 
 use self::{
-    command::RunnableCommand, commands::channel::ChannelCommand,
-    commands::export::ExportCommand, commands::new::NewCommand,
-    commands::pycharm::PycharmCommand,
+    command::Context, command::RunnableCommand,
+    commands::channel::ChannelCommand, commands::export::ExportCommand,
+    commands::i18n::I18nCommand, commands::import::ImportCommand,
+    commands::inspect::InspectCommand, commands::install::InstallCommand,
+    commands::new::NewCommand, commands::pack::PackCommand,
+    commands::pycharm::PycharmCommand, commands::verify::VerifyCommand,
+    commands::watch::WatchCommand,
 };
+use crate::utils::interactive;
+use clap::ArgMatches;
 
-pub fn run() -> Result<(), command::Error> {
+pub fn parse_args() -> ArgMatches {
     let matches = command::command().get_matches();
+    interactive::set_non_interactive(matches.get_flag("non-interactive"));
+
+    matches
+}
+
+// Commands that only read archives don't need the toolchain
+const ARCHIVE_COMMANDS: &[&str] = &["verify", "inspect"];
+
+fn needs_configs(command: &str) -> bool {
+    !ARCHIVE_COMMANDS.contains(&command)
+}
+
+pub fn run(matches: &ArgMatches) -> Result<(), command::Error> {
+    let context = if matches.subcommand_name().is_some_and(needs_configs) {
+        Context::load()
+            .map_err(|e| command::Error::CommandExecutionError(e.to_string()))?
+    } else {
+        Context::default()
+    };
 
     match matches.subcommand() {
-        | Some(("new", args)) => NewCommand::run(args),
-        | Some(("export", args)) => ExportCommand::run(args),
-        | Some(("pycharm", args)) => PycharmCommand::run(args),
-        | Some(("channel", args)) => ChannelCommand::run(args),
+        | Some(("new", args)) => NewCommand::run(args, &context),
+        | Some(("import", args)) => ImportCommand::run(args, &context),
+        | Some(("export", args)) => ExportCommand::run(args, &context),
+        | Some(("watch", args)) => WatchCommand::run(args, &context),
+        | Some(("install", args)) => InstallCommand::run(args, &context),
+        | Some(("verify", args)) => VerifyCommand::run(args, &context),
+        | Some(("inspect", args)) => InspectCommand::run(args, &context),
+        | Some(("pack", args)) => PackCommand::run(args, &context),
+        | Some(("i18n", args)) => I18nCommand::run(args, &context),
+        | Some(("pycharm", args)) => PycharmCommand::run(args, &context),
+        | Some(("channel", args)) => ChannelCommand::run(args, &context),
         | Some((_, _)) => Err(command::Error::CommandNotImplemented),
         | None => Err(command::Error::NoCommandProvided),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_commands_skip_configs() {
+        for name in ARCHIVE_COMMANDS {
+            assert!(command::command().find_subcommand(name).is_some());
+            assert!(!needs_configs(name));
+        }
+        assert!(needs_configs("export"));
+    }
+
+    #[test]
+    fn missing_configs_are_an_error() {
+        assert!(matches!(
+            Context::default().configs(),
+            Err(command::Error::ConfigsNotLoaded)
+        ));
+    }
+}
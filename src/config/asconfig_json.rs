@@ -1,6 +1,6 @@
 use serde_derive::{Deserialize, Serialize};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AsconfigcJson {
@@ -20,6 +20,16 @@ pub struct CompilerOption {
     pub source_path: Vec<String>,
     #[serde(rename = "external-library-path")]
     pub library_path: Vec<String>,
+    #[serde(rename = "define", default, skip_serializing_if = "Vec::is_empty")]
+    pub define: Vec<Define>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Define {
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "value")]
+    pub value: serde_json::Value,
 }
 
 impl AsconfigcJson {
@@ -32,6 +42,38 @@ impl AsconfigcJson {
         Ok(())
     }
 
+    // Replace the defines with the same name, keep the user ones
+    pub fn set_defines(&mut self, defines: Vec<Define>) {
+        self.compiler_option.define.retain(|define| {
+            !defines.iter().any(|other| other.name == define.name)
+        });
+        self.compiler_option.define.extend(defines);
+    }
+
+    // Paths are relative to the config file, they are made absolute so
+    // the config can be written anywhere
+    pub fn resolve_paths(&mut self, directory: &Path) -> Result<(), io::Error> {
+        let resolve = |path: &String| {
+            std::path::absolute(directory.join(path))
+                .map(|path| path.to_string_lossy().to_string())
+        };
+        let options = &mut self.compiler_option;
+
+        options.output = resolve(&options.output)?;
+        options.source_path = options
+            .source_path
+            .iter()
+            .map(resolve)
+            .collect::<Result<_, _>>()?;
+        options.library_path = options
+            .library_path
+            .iter()
+            .map(resolve)
+            .collect::<Result<_, _>>()?;
+
+        Ok(())
+    }
+
     pub fn from_file(filename: &PathBuf) -> Result<AsconfigcJson, io::Error> {
         let file = std::fs::File::open(filename)?;
         Ok(serde_json::from_reader(file)?)
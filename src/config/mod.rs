@@ -1,6 +1,7 @@
 pub mod asconfig_json;
 pub mod mod_conf;
 pub mod settings;
+pub mod workspace;
 
 use crate::config::settings::Settings;
 use crate::sdk::as3::AS3;
@@ -14,6 +15,7 @@ use crate::sdk::{
     as3, asconfigc, conda, flash_lib, game_sources, nvm, Installable,
 };
 use crate::utils;
+use crate::utils::interactive;
 use inquire::InquireError;
 use std::env::VarError;
 use std::path::PathBuf;
@@ -56,6 +58,9 @@ pub enum Error {
 
     #[error("Unable to read current user name")]
     Environment(#[from] VarError),
+
+    #[error("{0}")]
+    NonInteractiveError(#[from] interactive::Error),
 }
 
 type Result<T> = result::Result<T, Error>;
@@ -137,7 +142,7 @@ fn load_conda_environment(wg_mod_home: &PathBuf) -> Result<CondaEnvironment> {
     let conda = load_conda(wg_mod_home)?;
 
     if !conda.has_environment("wg-mod") {
-        println!("Create conda env...");
+        eprintln!("Create conda env...");
         conda.create_environment("wg-mod", "2")?;
     }
 
@@ -154,7 +159,7 @@ fn load_game_client(settings: &Settings) -> Option<GameClient> {
 
 fn load_settings(wg_mod_home: &PathBuf) -> Result<Settings> {
     let settings_file_path = wg_mod_home.join("settings.json");
-    let mut settings: Settings;
+    let settings: Settings;
 
     if !settings_file_path.exists() {
         settings =
@@ -163,7 +168,6 @@ fn load_settings(wg_mod_home: &PathBuf) -> Result<Settings> {
         settings = Settings::from_json_file(&settings_file_path)?;
     }
 
-    settings.verify_game_client_path_validity();
     settings.write_to_json_file()?;
 
     Ok(settings)
@@ -174,7 +178,7 @@ fn get_conda(wg_mod_home: &PathBuf) -> Result<Conda> {
     let conda = Conda::from(&conda_path);
 
     if !conda.is_installed() {
-        println!("Installing conda...");
+        eprintln!("Installing conda...");
         conda.install().expect("failed conda installation");
     }
 
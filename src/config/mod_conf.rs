@@ -1,7 +1,10 @@
 use crate::utils::file_template;
-use crate::utils::file_template::write_template;
+use crate::utils::file_template::{render_template, write_template};
+use regex::Regex;
+use semver::VersionReq;
 use serde_derive::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::io;
 use std::path::PathBuf;
 
@@ -9,9 +12,39 @@ use std::path::PathBuf;
 pub enum Error {
     #[error("Unable to create this template file")]
     FileTemplateError(#[from] file_template::Error),
+
+    #[error("Unable to parse meta.xml\n{0}")]
+    MetaParseError(#[from] roxmltree::Error),
+
+    #[error("Missing field <{0}> in meta.xml")]
+    MissingMetaFieldError(String),
+
+    #[error("Invalid dependency declaration: {0}")]
+    DependencyError(String),
+
+    #[error("Invalid archive name \"{0}\", it must be a file name without path separators")]
+    ArchiveNameError(String),
+
+    #[error("Invalid {0} \"{1}\", it must not contain path separators")]
+    FileNameFieldError(String, String),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+const DEFAULT_ARCHIVE_NAME: &str = "{{id}}_{{version}}.wotmod";
+
+// Rendered names and meta.xml values end up in file names, a path would
+// escape the folder they're written to
+fn is_file_name(value: &str) -> bool {
+    !value.is_empty()
+        && !value.contains(['/', '\\'])
+        && value != "."
+        && value != ".."
+}
+
+pub const VERSION_PATTERN: &str = r"^(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)$";
+pub const PACKAGE_NAME_PATTERN: &str =
+    r"^([a-z]{1}[a-z-\d_]*\.)+[a-z][a-z-\d_]*$";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ModConf {
     #[serde(rename = "id")]
     pub package_name: String,
@@ -21,6 +54,144 @@ pub struct ModConf {
     pub name: String,
     #[serde(rename = "description")]
     pub description: String,
+    #[serde(
+        rename = "main_class",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub main_class: Option<String>,
+    #[serde(rename = "ui", default, skip_serializing_if = "Option::is_none")]
+    pub ui: Option<bool>,
+    #[serde(
+        rename = "archive_name",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub archive_name: Option<String>,
+    #[serde(
+        rename = "resources",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub resources: Vec<ResourceMapping>,
+    #[serde(
+        rename = "locale",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub locale: Option<String>,
+    #[serde(
+        rename = "ui_targets",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub ui_targets: Vec<UiTarget>,
+    #[serde(
+        rename = "dependencies",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub dependencies: BTreeMap<String, String>,
+    #[serde(
+        rename = "conflicts",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub conflicts: BTreeMap<String, String>,
+    #[serde(
+        rename = "hooks",
+        default,
+        skip_serializing_if = "Hooks::is_empty"
+    )]
+    pub hooks: Hooks,
+    #[serde(
+        rename = "profile",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub profile: Option<Profile>,
+}
+
+// Release builds strip asserts and docstrings from the bytecode and don't
+// leak local paths into tracebacks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    #[default]
+    Debug,
+    Release,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Hooks {
+    #[serde(
+        rename = "pre_build",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub pre_build: Vec<String>,
+    #[serde(
+        rename = "post_build",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub post_build: Vec<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_build.is_empty() && self.post_build.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UiTarget {
+    #[serde(rename = "main_class")]
+    pub main_class: String,
+    #[serde(rename = "output")]
+    pub output: String,
+    #[serde(
+        rename = "config",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub config: Option<PathBuf>,
+}
+
+impl UiTarget {
+    pub fn output_file_name(&self) -> String {
+        if self.output.ends_with(".swf") {
+            self.output.clone()
+        } else {
+            format!("{}.swf", self.output)
+        }
+    }
+
+    pub fn config_path(&self) -> PathBuf {
+        self.config
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("asconfig.json"))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceMapping {
+    #[serde(rename = "source")]
+    pub source: PathBuf,
+    #[serde(rename = "destination", default)]
+    pub destination: PathBuf,
+    #[serde(
+        rename = "include",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub include: Vec<String>,
+    #[serde(
+        rename = "exclude",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub exclude: Vec<String>,
 }
 
 impl ModConf {
@@ -44,21 +215,277 @@ impl ModConf {
   <version>{{version}}</version>
   <name>{{name}}</name>
   <description>{{description}}</description>
+{{#if dependencies}}
+  <dependencies>
+{{#each dependencies}}
+    <dependency id=\"{{@key}}\" version=\"{{this}}\" />
+{{/each}}
+  </dependencies>
+{{/if}}
+{{#if conflicts}}
+  <conflicts>
+{{#each conflicts}}
+    <conflict id=\"{{@key}}\" version=\"{{this}}\" />
+{{/each}}
+  </conflicts>
+{{/if}}
 </root>
  ",
             &json!({
                 "package_name": self.package_name,
                 "version": self.version,
                 "name": self.name,
-                "description": self.description
+                "description": self.description,
+                "dependencies": self.dependencies,
+                "conflicts": self.conflicts
             }),
         )?;
 
         Ok(())
     }
 
+    pub fn archive_file_name(&self) -> Result<String, Error> {
+        let template =
+            self.archive_name.as_deref().unwrap_or(DEFAULT_ARCHIVE_NAME);
+        let file_name = render_template(
+            template,
+            &json!({
+                "id": self.package_name,
+                "version": self.version,
+                "name": self.name,
+            }),
+        )?;
+
+        if !is_file_name(&file_name) {
+            return Err(Error::ArchiveNameError(file_name));
+        }
+
+        if file_name.ends_with(".wotmod") {
+            Ok(file_name)
+        } else {
+            Ok(format!("{file_name}.wotmod"))
+        }
+    }
+
+    // The id and version of an archive name its installed file
+    pub fn validate_file_name_fields(&self) -> Result<(), Error> {
+        for (field, value) in
+            [("id", &self.package_name), ("version", &self.version)]
+        {
+            if !is_file_name(value) {
+                return Err(Error::FileNameFieldError(
+                    field.to_string(),
+                    value.clone(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn from_meta(content: &str) -> Result<ModConf, Error> {
+        let document = roxmltree::Document::parse(content)?;
+        let root = document.root_element();
+        let field = |name: &str| {
+            root.children()
+                .find(|node| node.has_tag_name(name))
+                .map(|node| node.text().unwrap_or("").trim().to_string())
+        };
+        let required_field = |name: &str| {
+            field(name).ok_or(Error::MissingMetaFieldError(name.to_string()))
+        };
+
+        let requirements = |list: &str, item: &str| {
+            root.children()
+                .filter(|node| node.has_tag_name(list))
+                .flat_map(|node| node.children())
+                .filter(|node| node.has_tag_name(item))
+                .filter_map(|node| {
+                    let id = node.attribute("id")?.to_string();
+                    let version = node.attribute("version").unwrap_or("*");
+                    Some((id, version.to_string()))
+                })
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        Ok(ModConf {
+            package_name: required_field("id")?,
+            version: required_field("version")?,
+            name: required_field("name")?,
+            description: field("description").unwrap_or_default(),
+            dependencies: requirements("dependencies", "dependency"),
+            conflicts: requirements("conflicts", "conflict"),
+            ..Default::default()
+        })
+    }
+
+    pub fn validate_dependencies(&self) -> Result<(), Error> {
+        let package_name = Regex::new(PACKAGE_NAME_PATTERN).unwrap();
+        let declarations = self
+            .dependencies
+            .iter()
+            .map(|declaration| ("dependency", declaration))
+            .chain(
+                self.conflicts
+                    .iter()
+                    .map(|declaration| ("conflict", declaration)),
+            );
+
+        for (kind, (id, range)) in declarations {
+            if !package_name.is_match(id) {
+                return Err(Error::DependencyError(format!(
+                    "{kind} \"{id}\" is not a valid package id"
+                )));
+            }
+            if id == &self.package_name {
+                return Err(Error::DependencyError(format!(
+                    "{kind} \"{id}\" is the mod itself"
+                )));
+            }
+            VersionReq::parse(range).map_err(|e| {
+                Error::DependencyError(format!(
+                    "{kind} \"{id}\" has an invalid version range \"{range}\": {e}"
+                ))
+            })?;
+        }
+
+        if let Some(id) = self
+            .dependencies
+            .keys()
+            .find(|id| self.conflicts.contains_key(*id))
+        {
+            return Err(Error::DependencyError(format!(
+                "\"{id}\" is both a dependency and a conflict"
+            )));
+        }
+
+        Ok(())
+    }
+
     pub fn from_file(filename: &PathBuf) -> Result<ModConf, io::Error> {
         let file = std::fs::File::open(filename)?;
         Ok(serde_json::from_reader(file)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+    use tempfile::tempdir;
+
+    #[test]
+    fn meta_round_trip() {
+        let tmp_dir = tempdir().unwrap();
+        let mod_conf = ModConf {
+            package_name: "fr.gabouchet.better-matchmaking".to_string(),
+            version: "1.0.2".to_string(),
+            name: "Better matchmaking".to_string(),
+            description: "Best mod ever".to_string(),
+            ..Default::default()
+        };
+
+        mod_conf
+            .export_mod_meta(&tmp_dir.path().to_path_buf(), "meta.xml")
+            .unwrap();
+        let meta = read_to_string(tmp_dir.path().join("meta.xml")).unwrap();
+        let parsed = ModConf::from_meta(&meta).unwrap();
+
+        assert_eq!(parsed.package_name, mod_conf.package_name);
+        assert_eq!(parsed.version, mod_conf.version);
+        assert_eq!(parsed.name, mod_conf.name);
+        assert_eq!(parsed.description, mod_conf.description);
+
+        tmp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn archive_file_name() {
+        let mut mod_conf = ModConf {
+            package_name: "com.example.mod".to_string(),
+            version: "1.0.2".to_string(),
+            name: "Mod".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            mod_conf.archive_file_name().unwrap(),
+            "com.example.mod_1.0.2.wotmod"
+        );
+
+        mod_conf.archive_name = Some("{{name}}-{{version}}".to_string());
+        assert_eq!(mod_conf.archive_file_name().unwrap(), "Mod-1.0.2.wotmod");
+
+        for archive_name in ["../{{id}}.wotmod", "{{id}}\\dist", ".."] {
+            mod_conf.archive_name = Some(archive_name.to_string());
+            assert!(matches!(
+                mod_conf.archive_file_name(),
+                Err(Error::ArchiveNameError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn ui_targets_from_json() {
+        let mod_conf: ModConf = serde_json::from_str(
+            r#"{
+                "id": "com.example.mod",
+                "version": "1.0.0",
+                "name": "Mod",
+                "description": "",
+                "profile": "release",
+                "ui_targets": [
+                    { "main_class": "my.mod.Settings", "output": "settings" },
+                    { "main_class": "my.mod.Battle", "output": "battle.swf", "config": "battle/asconfig.json" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(mod_conf.profile, Some(Profile::Release));
+        let targets = mod_conf.ui_targets;
+        assert_eq!(targets[0].output_file_name(), "settings.swf");
+        assert_eq!(targets[0].config_path(), PathBuf::from("asconfig.json"));
+        assert_eq!(targets[1].output_file_name(), "battle.swf");
+        assert_eq!(
+            targets[1].config_path(),
+            PathBuf::from("battle/asconfig.json")
+        );
+    }
+
+    #[test]
+    fn dependencies_in_meta() {
+        let tmp_dir = tempdir().unwrap();
+        let mut mod_conf = ModConf {
+            package_name: "com.example.mod".to_string(),
+            version: "1.0.0".to_string(),
+            name: "Mod".to_string(),
+            ..Default::default()
+        };
+        mod_conf
+            .dependencies
+            .insert("com.example.lib".to_string(), ">=1.2, <2".to_string());
+        mod_conf
+            .conflicts
+            .insert("com.other.mod".to_string(), "*".to_string());
+        mod_conf.validate_dependencies().unwrap();
+
+        mod_conf
+            .export_mod_meta(&tmp_dir.path().to_path_buf(), "meta.xml")
+            .unwrap();
+        let meta = read_to_string(tmp_dir.path().join("meta.xml")).unwrap();
+        let parsed = ModConf::from_meta(&meta).unwrap();
+        assert_eq!(parsed.dependencies, mod_conf.dependencies);
+        assert_eq!(parsed.conflicts, mod_conf.conflicts);
+
+        mod_conf
+            .conflicts
+            .insert("com.example.lib".to_string(), "*".to_string());
+        assert!(mod_conf.validate_dependencies().is_err());
+
+        mod_conf.conflicts.clear();
+        mod_conf
+            .dependencies
+            .insert("com.example.lib".to_string(), "one".to_string());
+        assert!(mod_conf.validate_dependencies().is_err());
+    }
+}
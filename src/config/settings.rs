@@ -2,6 +2,7 @@ use crate::config;
 use crate::config::get_tool_home;
 use crate::config::settings::Error::LoadError;
 use crate::utils::convert_pathbuf_to_string::Stringify;
+use crate::utils::interactive;
 use serde_derive::{Deserialize, Serialize};
 use std::env;
 use std::fs::File;
@@ -16,6 +17,10 @@ pub enum Error {
     ParsingError(String),
     #[error("Failed to load settings : {0}")]
     LoadError(String),
+    #[error("No valid game client path configured")]
+    MissingGameClientError,
+    #[error("{0}")]
+    NonInteractiveError(#[from] interactive::Error),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -35,6 +40,8 @@ impl Settings {
     }
 
     fn prompt_game_client_path(&self) -> Result<String, config::Error> {
+        interactive::ensure_interactive("WoT client path")?;
+
         let default_game_client_path = if cfg!(target_os = "windows") {
             PathBuf::from("C:\\Games\\World_of_Tanks_EU")
         } else {
@@ -81,17 +88,43 @@ impl Settings {
             };
 
             if !is_path_valid {
-                println!();
-                println!("--- Pay attention ---");
-                println!("Game client path doesn't exist: {:?}", new_path);
-                println!("You will not be able to build");
-                println!("to set it, rerun wg-mod command");
-                println!("---------------------");
-                println!();
+                eprintln!();
+                eprintln!("--- Pay attention ---");
+                eprintln!("Game client path doesn't exist: {:?}", new_path);
+                eprintln!("You will not be able to build");
+                eprintln!("to set it, rerun wg-mod command");
+                eprintln!("---------------------");
+                eprintln!();
             }
         }
     }
 
+    pub fn require_game_client_path(&mut self) -> Result<PathBuf, Error> {
+        self.ensure_game_client_path_input(interactive::is_interactive())?;
+
+        self.verify_game_client_path_validity();
+        self.write_to_json_file()?;
+
+        self.game_client_path
+            .clone()
+            .ok_or(Error::MissingGameClientError)
+    }
+
+    // Prompting can't succeed in non-interactive mode, say so instead
+    fn ensure_game_client_path_input(
+        &self, interactive: bool,
+    ) -> Result<(), Error> {
+        if !self
+            .game_client_path
+            .as_ref()
+            .is_some_and(|path| path.exists())
+        {
+            interactive::require_input(interactive, "WoT client path")?;
+        }
+
+        Ok(())
+    }
+
     pub fn write_to_json_file(&self) -> Result<(), Error> {
         let file = File::create(&self.settings_file_path)?;
 
@@ -117,3 +150,25 @@ pub fn load_settings() -> Result<Settings, Error> {
 
     Ok(settings)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn missing_game_client_path_in_non_interactive_mode() {
+        let tmp_dir = tempdir().unwrap();
+        let settings = Settings::create_default_settings(
+            tmp_dir.path().join("settings.json"),
+        );
+
+        let error = settings.ensure_game_client_path_input(false).unwrap_err();
+
+        assert!(matches!(error, Error::NonInteractiveError(_)));
+        assert_eq!(
+            error.to_string(),
+            "Input required for \"WoT client path\" but wg-mod runs in non-interactive mode"
+        );
+    }
+}
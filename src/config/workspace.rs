@@ -0,0 +1,176 @@
+use crate::config::mod_conf::ModConf;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::{io, result};
+
+pub const WORKSPACE_FILE: &str = "wg-mod-workspace.json";
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to read {0:?}\n{1}")]
+    ReadError(PathBuf, io::Error),
+
+    #[error("Invalid member pattern \"{0}\"\n{1}")]
+    PatternError(String, glob::PatternError),
+
+    #[error("No workspace member with package id {0}")]
+    UnknownMemberError(String),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Workspace {
+    #[serde(rename = "members")]
+    pub members: Vec<String>,
+}
+
+impl Workspace {
+    pub fn from_file(filename: &PathBuf) -> Result<Workspace> {
+        let read = || -> result::Result<Workspace, io::Error> {
+            let file = std::fs::File::open(filename)?;
+            Ok(serde_json::from_reader(file)?)
+        };
+
+        read().map_err(|e| Error::ReadError(filename.clone(), e))
+    }
+
+    // Members are folders relative to the workspace root, glob patterns
+    // only match folders holding a mod.json
+    pub fn member_paths(&self, root: &PathBuf) -> Result<Vec<PathBuf>> {
+        let mut paths = vec![];
+
+        for member in &self.members {
+            let is_pattern = member.contains(['*', '?', '[']);
+            if !is_pattern {
+                paths.push(root.join(member));
+                continue;
+            }
+
+            let pattern = root.join(member).to_string_lossy().to_string();
+            let mut matches = glob::glob(&pattern)
+                .map_err(|e| Error::PatternError(member.clone(), e))?
+                .flatten()
+                .filter(|path| path.join("mod.json").is_file())
+                .collect::<Vec<_>>();
+            matches.sort();
+            paths.extend(matches);
+        }
+
+        // A folder listed explicitly and matched by a pattern is built once,
+        // at its first position
+        let mut seen = HashSet::new();
+        paths.retain(|path| {
+            seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone()))
+        });
+
+        Ok(paths)
+    }
+}
+
+pub struct Member {
+    pub path: PathBuf,
+    pub mod_conf: Option<ModConf>,
+}
+
+impl Member {
+    pub fn package_name(&self) -> Option<&String> {
+        self.mod_conf
+            .as_ref()
+            .map(|mod_conf| &mod_conf.package_name)
+    }
+}
+
+// Every member when no package id is given, otherwise the matching ones
+pub fn select_members(
+    root: &PathBuf, packages: &[String],
+) -> Result<Vec<Member>> {
+    let workspace = Workspace::from_file(&root.join(WORKSPACE_FILE))?;
+
+    let members = workspace
+        .member_paths(root)?
+        .into_iter()
+        .map(|path| Member {
+            mod_conf: ModConf::from_file(&path.join("mod.json")).ok(),
+            path,
+        })
+        .collect::<Vec<_>>();
+
+    if packages.is_empty() {
+        return Ok(members);
+    }
+
+    if let Some(unknown) = packages.iter().find(|package| {
+        !members
+            .iter()
+            .any(|member| member.package_name() == Some(package))
+    }) {
+        return Err(Error::UnknownMemberError(unknown.to_string()));
+    }
+
+    Ok(members
+        .into_iter()
+        .filter(|member| {
+            member
+                .package_name()
+                .is_some_and(|package_name| packages.contains(package_name))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, write};
+    use tempfile::tempdir;
+
+    #[test]
+    fn workspace_members() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path().to_path_buf();
+        for member in ["mods/first", "mods/second", "tools/shared"] {
+            create_dir_all(root.join(member)).unwrap();
+        }
+        write(root.join("mods/first/mod.json"), "{}").unwrap();
+        write(root.join("mods/second/mod.json"), "{}").unwrap();
+        write(
+            root.join(WORKSPACE_FILE),
+            r#"{ "members": ["mods/*", "ui-kit"] }"#,
+        )
+        .unwrap();
+
+        let workspace =
+            Workspace::from_file(&root.join(WORKSPACE_FILE)).unwrap();
+        assert_eq!(
+            workspace.member_paths(&root).unwrap(),
+            vec![
+                root.join("mods/first"),
+                root.join("mods/second"),
+                root.join("ui-kit"),
+            ]
+        );
+    }
+
+    #[test]
+    fn workspace_members_are_listed_once() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path().to_path_buf();
+        for member in ["mods/first", "mods/second"] {
+            create_dir_all(root.join(member)).unwrap();
+            write(root.join(member).join("mod.json"), "{}").unwrap();
+        }
+        write(
+            root.join(WORKSPACE_FILE),
+            r#"{ "members": ["mods/second", "mods/*", "./mods/first"] }"#,
+        )
+        .unwrap();
+
+        let workspace =
+            Workspace::from_file(&root.join(WORKSPACE_FILE)).unwrap();
+        assert_eq!(
+            workspace.member_paths(&root).unwrap(),
+            vec![root.join("mods/second"), root.join("mods/first")]
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use crate::i18n::po::escape;
+use fs_extra::dir::get_dir_content;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid extraction pattern\n{0}")]
+    PatternError(#[from] regex::Error),
+
+    #[error("Unable to list source files\n{0}")]
+    ListError(#[from] fs_extra::error::Error),
+
+    #[error("Unable to read source file\n{0}")]
+    ReadError(#[from] io::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+// Python: _("text"), i18n.makeString("#domain:key")
+const PYTHON_PATTERNS: [&str; 2] = [
+    r#"(?:\b_|\bmakeString)\(\s*"((?:[^"\\]|\\.)*)""#,
+    r#"(?:\b_|\bmakeString)\(\s*'((?:[^'\\]|\\.)*)'"#,
+];
+
+// AS3: any "#domain:key" string literal
+const AS3_PATTERNS: [&str; 2] = [
+    r#""(#[\w.-]+:(?:[^"\\]|\\.)+)""#,
+    r#"'(#[\w.-]+:(?:[^'\\]|\\.)+)'"#,
+];
+
+#[derive(Default)]
+pub struct Catalog {
+    messages: BTreeMap<String, Vec<String>>,
+}
+
+// The .pot escapes the id again, so source escapes are resolved first.
+// Unknown escapes are kept as written, like Python does
+fn unescape(literal: &str) -> String {
+    let mut result = String::new();
+    let mut chars = literal.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            result.push(char);
+            continue;
+        }
+
+        match chars.next() {
+            | Some('n') => result.push('\n'),
+            | Some('t') => result.push('\t'),
+            | Some('r') => result.push('\r'),
+            | Some(quote @ ('"' | '\'' | '\\')) => result.push(quote),
+            | Some(other) => {
+                result.push('\\');
+                result.push(other);
+            },
+            | None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+fn message_id(literal: &str) -> String {
+    let literal = unescape(literal);
+
+    // WoT keys look like "#domain:path/key", the catalog only holds the key
+    match literal
+        .strip_prefix('#')
+        .and_then(|key| key.split_once(':'))
+    {
+        | Some((_, key)) => key.to_string(),
+        | None => literal,
+    }
+}
+
+impl Catalog {
+    pub fn scan_directory(
+        &mut self, root: &PathBuf, directory: &PathBuf,
+    ) -> Result<()> {
+        if !directory.exists() {
+            return Ok(());
+        }
+
+        let mut files = get_dir_content(directory)?.files;
+        files.sort();
+
+        for file in files.into_iter().map(PathBuf::from) {
+            let patterns = match file.extension().and_then(|e| e.to_str()) {
+                | Some("py") => PYTHON_PATTERNS,
+                | Some("as") => AS3_PATTERNS,
+                | _ => continue,
+            };
+            let reference = file
+                .strip_prefix(root)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .replace('\\', "/");
+
+            self.scan(&read_to_string(&file)?, &reference, &patterns)?;
+        }
+
+        Ok(())
+    }
+
+    fn scan(
+        &mut self, content: &str, reference: &str, patterns: &[&str],
+    ) -> Result<()> {
+        for pattern in patterns {
+            let regex = Regex::new(pattern)?;
+
+            for captures in regex.captures_iter(content) {
+                let Some(literal) = captures.get(1) else {
+                    continue;
+                };
+                let line = content[..literal.start()].lines().count().max(1);
+
+                self.messages
+                    .entry(message_id(literal.as_str()))
+                    .or_default()
+                    .push(format!("{reference}:{line}"));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_pot(&self) -> String {
+        let mut pot = String::from(
+            "msgid \"\"
+msgstr \"\"
+\"Content-Type: text/plain; charset=UTF-8\\n\"
+\"Content-Transfer-Encoding: 8bit\\n\"
+",
+        );
+
+        for (id, references) in &self.messages {
+            if id.is_empty() {
+                continue;
+            }
+
+            pot.push('\n');
+            pot.push_str(&format!("#: {}\n", references.join(" ")));
+            pot.push_str(&format!("msgid \"{}\"\n", escape(id)));
+            pot.push_str("msgstr \"\"\n");
+        }
+
+        pot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_python_and_as3_messages() {
+        let mut catalog = Catalog::default();
+
+        catalog
+            .scan(
+                "from helpers import i18n\n\nprint(_('Hello'))\nlabel = i18n.makeString(\"#my_mod:settings/title\")\n",
+                "scripts/mod_test.py",
+                &PYTHON_PATTERNS,
+            )
+            .unwrap();
+        catalog
+            .scan(
+                "var title:String = App.utils.locale.makeString(\"#my_mod:settings/title\");\n",
+                "ui/src/Main.as",
+                &AS3_PATTERNS,
+            )
+            .unwrap();
+
+        assert_eq!(
+            catalog.to_pot(),
+            "msgid \"\"
+msgstr \"\"
+\"Content-Type: text/plain; charset=UTF-8\\n\"
+\"Content-Transfer-Encoding: 8bit\\n\"
+
+#: scripts/mod_test.py:3
+msgid \"Hello\"
+msgstr \"\"
+
+#: scripts/mod_test.py:4 ui/src/Main.as:1
+msgid \"settings/title\"
+msgstr \"\"
+"
+        );
+    }
+
+    #[test]
+    fn unescape_source_literals() {
+        let mut catalog = Catalog::default();
+
+        catalog
+            .scan(
+                r#"_("Line\none \"quoted\" C:\\temp \d")"#,
+                "scripts/mod_test.py",
+                &PYTHON_PATTERNS,
+            )
+            .unwrap();
+
+        assert!(catalog
+            .to_pot()
+            .contains(r#"msgid "Line\none \"quoted\" C:\\temp \\d""#));
+    }
+}
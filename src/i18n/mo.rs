@@ -0,0 +1,138 @@
+use crate::i18n::po::Message;
+
+const MAGIC: u32 = 0x950412de;
+const HEADER_SIZE: usize = 28;
+
+fn original_key(message: &Message) -> String {
+    let mut key = match &message.context {
+        | Some(context) => format!("{context}\u{4}{}", message.id),
+        | None => message.id.clone(),
+    };
+
+    if let Some(id_plural) = &message.id_plural {
+        key.push('\0');
+        key.push_str(id_plural);
+    }
+
+    key
+}
+
+fn push_u32(buffer: &mut Vec<u8>, value: usize) {
+    buffer.extend_from_slice(&(value as u32).to_le_bytes());
+}
+
+pub fn compile(messages: &[Message]) -> Vec<u8> {
+    let mut entries = messages
+        .iter()
+        .filter(|message| {
+            message.id.is_empty()
+                || (!message.fuzzy
+                    && message
+                        .translations
+                        .iter()
+                        .any(|value| !value.is_empty()))
+        })
+        .map(|message| (original_key(message), message.translations.join("\0")))
+        .collect::<Vec<_>>();
+    entries.sort_by(|(left, _), (right, _)| left.cmp(right));
+    entries.dedup_by(|(left, _), (right, _)| left == right);
+
+    let count = entries.len();
+    let originals_offset = HEADER_SIZE;
+    let translations_offset = originals_offset + count * 8;
+    let mut data_offset = translations_offset + count * 8;
+
+    let mut buffer = vec![];
+    push_u32(&mut buffer, MAGIC as usize);
+    push_u32(&mut buffer, 0);
+    push_u32(&mut buffer, count);
+    push_u32(&mut buffer, originals_offset);
+    push_u32(&mut buffer, translations_offset);
+    push_u32(&mut buffer, 0);
+    push_u32(&mut buffer, data_offset);
+
+    let mut tables = vec![];
+    let mut data = vec![];
+    for strings in [
+        entries
+            .iter()
+            .map(|(original, _)| original)
+            .collect::<Vec<_>>(),
+        entries.iter().map(|(_, translation)| translation).collect(),
+    ] {
+        for string in strings {
+            push_u32(&mut tables, string.len());
+            push_u32(&mut tables, data_offset);
+            data.extend_from_slice(string.as_bytes());
+            data.push(0);
+            data_offset += string.len() + 1;
+        }
+    }
+
+    buffer.extend(tables);
+    buffer.extend(data);
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::po;
+
+    fn read_u32(buffer: &[u8], offset: usize) -> usize {
+        u32::from_le_bytes(buffer[offset..offset + 4].try_into().unwrap())
+            as usize
+    }
+
+    fn read_string(buffer: &[u8], table: usize, index: usize) -> String {
+        let length = read_u32(buffer, table + index * 8);
+        let offset = read_u32(buffer, table + index * 8 + 4);
+        String::from_utf8(buffer[offset..offset + length].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn compile_po_file() {
+        let messages = po::parse(
+            r#"
+msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+
+#: scripts/mod_test.py:3
+msgid "hello"
+msgstr "bonjour"
+
+#, fuzzy
+msgid "fuzzy"
+msgstr "flou"
+
+msgctxt "menu"
+msgid "tank"
+msgid_plural "tanks"
+msgstr[0] "char"
+msgstr[1] "chars"
+
+msgid "untranslated"
+msgstr ""
+"#,
+        )
+        .unwrap();
+
+        let mo = compile(&messages);
+
+        assert_eq!(read_u32(&mo, 0), MAGIC as usize);
+        assert_eq!(read_u32(&mo, 8), 3);
+        let originals = read_u32(&mo, 12);
+        let translations = read_u32(&mo, 16);
+
+        assert_eq!(read_string(&mo, originals, 0), "");
+        assert_eq!(
+            read_string(&mo, translations, 0),
+            "Content-Type: text/plain; charset=UTF-8\n"
+        );
+        assert_eq!(read_string(&mo, originals, 1), "hello");
+        assert_eq!(read_string(&mo, translations, 1), "bonjour");
+        assert_eq!(read_string(&mo, originals, 2), "menu\u{4}tank\0tanks");
+        assert_eq!(read_string(&mo, translations, 2), "char\0chars");
+    }
+}
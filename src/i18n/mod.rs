@@ -0,0 +1,84 @@
+pub mod extract;
+pub mod mo;
+pub mod po;
+
+use std::fs::{create_dir_all, read_dir, read_to_string, write};
+use std::path::PathBuf;
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to parse {0:?}\n{1}")]
+    ParseError(PathBuf, po::Error),
+
+    #[error("Unable to read or write translations\n{0}")]
+    FileError(#[from] io::Error),
+
+    #[error("Locale \"{0}\" not found in locales/")]
+    MissingLocaleError(String),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub fn list_locales(locales_path: &PathBuf) -> Result<Vec<String>> {
+    let mut locales = read_dir(locales_path)?
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    locales.sort();
+
+    Ok(locales)
+}
+
+// The game only reads res/text/lc_messages, one locale has to be picked for
+// it. None when it's ambiguous and "locale" isn't set in mod.json
+pub fn select_locale(
+    locales: &[String], requested: Option<&str>,
+) -> Result<Option<String>> {
+    if let Some(requested) = requested {
+        return match locales.iter().find(|locale| *locale == requested) {
+            | Some(locale) => Ok(Some(locale.clone())),
+            | None => Err(Error::MissingLocaleError(requested.to_string())),
+        };
+    }
+
+    match locales {
+        | [] => Ok(None),
+        | [locale] => Ok(Some(locale.clone())),
+        | _ if locales.iter().any(|locale| locale == "en") => {
+            Ok(Some("en".to_string()))
+        },
+        | _ => Ok(None),
+    }
+}
+
+pub fn compile_locale(
+    locale_path: &PathBuf, destination: &PathBuf,
+) -> Result<()> {
+    create_dir_all(destination)?;
+
+    for entry in read_dir(locale_path)?.flatten() {
+        let po_path = entry.path();
+        if po_path
+            .extension()
+            .is_none_or(|extension| extension != "po")
+        {
+            continue;
+        }
+
+        let messages = po::parse(&read_to_string(&po_path)?)
+            .map_err(|e| Error::ParseError(po_path.clone(), e))?;
+        let mo_path = po_path
+            .file_stem()
+            .map(|domain| destination.join(domain).with_extension("mo"))
+            .ok_or(Error::ParseError(
+                po_path.clone(),
+                po::Error::SyntaxError(0, "invalid file name".to_string()),
+            ))?;
+
+        write(mo_path, mo::compile(&messages))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use std::result;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Line {0}: {1}")]
+    SyntaxError(usize, String),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Message {
+    pub context: Option<String>,
+    pub id: String,
+    pub id_plural: Option<String>,
+    pub translations: Vec<String>,
+    pub fuzzy: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Context,
+    Id,
+    IdPlural,
+    Translation(usize),
+}
+
+fn unescape(value: &str, line_number: usize) -> Result<String> {
+    let mut result = String::new();
+    let mut chars = value.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            result.push(char);
+            continue;
+        }
+
+        match chars.next() {
+            | Some('n') => result.push('\n'),
+            | Some('t') => result.push('\t'),
+            | Some('r') => result.push('\r'),
+            | Some('"') => result.push('"'),
+            | Some('\\') => result.push('\\'),
+            | Some(other) => {
+                return Err(Error::SyntaxError(
+                    line_number,
+                    format!("unknown escape sequence \\{other}"),
+                ))
+            },
+            | None => {
+                return Err(Error::SyntaxError(
+                    line_number,
+                    "unterminated escape sequence".to_string(),
+                ))
+            },
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r")
+}
+
+fn parse_string(value: &str, line_number: usize) -> Result<String> {
+    let value = value.trim();
+    if value.len() < 2 || !value.starts_with('"') || !value.ends_with('"') {
+        return Err(Error::SyntaxError(
+            line_number,
+            format!("expected a quoted string, found {value}"),
+        ));
+    }
+
+    unescape(&value[1..value.len() - 1], line_number)
+}
+
+fn parse_keyword(line: &str, line_number: usize) -> Result<(Field, &str)> {
+    let (keyword, rest) =
+        line.split_once(char::is_whitespace)
+            .ok_or(Error::SyntaxError(
+                line_number,
+                format!("unexpected line {line}"),
+            ))?;
+
+    let field = match keyword {
+        | "msgctxt" => Field::Context,
+        | "msgid" => Field::Id,
+        | "msgid_plural" => Field::IdPlural,
+        | "msgstr" => Field::Translation(0),
+        | _ => {
+            let index = keyword
+                .strip_prefix("msgstr[")
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(|index| index.parse::<usize>().ok())
+                .ok_or(Error::SyntaxError(
+                    line_number,
+                    format!("unknown keyword {keyword}"),
+                ))?;
+            Field::Translation(index)
+        },
+    };
+
+    Ok((field, rest))
+}
+
+fn push_message(messages: &mut Vec<Message>, message: &mut Message) {
+    let message = std::mem::take(message);
+
+    if !message.translations.is_empty() {
+        messages.push(message);
+    }
+}
+
+pub fn parse(content: &str) -> Result<Vec<Message>> {
+    let mut messages = vec![];
+    let mut message = Message::default();
+    let mut current_field: Option<Field> = None;
+    let mut next_fuzzy = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(flags) = line.strip_prefix("#,") {
+            next_fuzzy = flags.split(',').any(|flag| flag.trim() == "fuzzy");
+            continue;
+        }
+
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('"') {
+            let value = parse_string(line, line_number)?;
+            match current_field {
+                | Some(Field::Context) => message
+                    .context
+                    .get_or_insert_with(String::new)
+                    .push_str(&value),
+                | Some(Field::Id) => message.id.push_str(&value),
+                | Some(Field::IdPlural) => message
+                    .id_plural
+                    .get_or_insert_with(String::new)
+                    .push_str(&value),
+                | Some(Field::Translation(index)) => {
+                    message.translations[index].push_str(&value)
+                },
+                | None => {
+                    return Err(Error::SyntaxError(
+                        line_number,
+                        "string without keyword".to_string(),
+                    ))
+                },
+            }
+            continue;
+        }
+
+        let (field, rest) = parse_keyword(line, line_number)?;
+        let value = parse_string(rest, line_number)?;
+
+        let starts_new_message = match field {
+            | Field::Context => true,
+            | Field::Id => current_field != Some(Field::Context),
+            | _ => false,
+        };
+        if starts_new_message {
+            push_message(&mut messages, &mut message);
+            message.fuzzy = next_fuzzy;
+            next_fuzzy = false;
+        }
+
+        match field {
+            | Field::Context => message.context = Some(value),
+            | Field::Id => message.id = value,
+            | Field::IdPlural => message.id_plural = Some(value),
+            | Field::Translation(index) => {
+                if index >= message.translations.len() {
+                    message.translations.resize(index + 1, String::new());
+                }
+                message.translations[index] = value;
+            },
+        }
+        current_field = Some(field);
+    }
+
+    push_message(&mut messages, &mut message);
+
+    Ok(messages)
+}
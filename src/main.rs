@@ -1,17 +1,17 @@
-use crate::config::Configs;
-
 mod builder;
 mod cli;
 mod config;
+mod i18n;
 mod new;
 mod sdk;
 mod utils;
+mod wotmod;
 
 fn main() {
-    Configs::load().expect("Unable to load wg-mod configs");
+    let matches = cli::parse_args();
 
-    match cli::run() {
-        | Err(err) => eprintln!("{}", err.to_string()),
-        | _ => (),
-    };
+    if let Err(err) = cli::run(&matches) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
 }
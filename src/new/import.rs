@@ -0,0 +1,139 @@
+use crate::config::mod_conf;
+use crate::config::mod_conf::ModConf;
+use crate::new::template::init_git_repository;
+use crate::utils::convert_to_absolute_path;
+use crate::utils::convert_to_absolute_path::convert_to_absolute_path;
+use crate::utils::{file_template, zip};
+use convert_case::{Case, Casing};
+use fs_extra::dir::get_dir_content;
+use std::fs::{copy, create_dir_all, read_to_string};
+use std::path::{Path, PathBuf};
+use std::{io, result};
+use tempfile::tempdir;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to extract the archive\n{0}")]
+    ExtractError(#[from] zip::Error),
+
+    #[error("The archive has no meta.xml")]
+    MissingMetaError,
+
+    #[error("Invalid meta.xml\n{0}")]
+    MetaError(#[from] mod_conf::Error),
+
+    #[error("The directory {0:?} already exists")]
+    ExistingDirectoryError(PathBuf),
+
+    #[error("Unable to list the archive content\n{0}")]
+    ListError(#[from] fs_extra::error::Error),
+
+    #[error("Unable to write the project files\n{0}")]
+    WriteError(#[from] io::Error),
+
+    #[error("Unable to create the project\n{0}")]
+    TemplateError(#[from] file_template::Error),
+
+    #[error("Unable to display absolute mod path")]
+    AbsolutePathError(#[from] convert_to_absolute_path::Error),
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+const PYTHON_MODS_PATH: &str = "res/scripts/client/gui/mods";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub root_path: PathBuf,
+    pub compiled_only: Vec<PathBuf>,
+}
+
+// Python mods go back to scripts/ as create_mod_files lays them out,
+// everything else stays under res/ and is bundled by the resources stage
+fn project_path(relative: &Path) -> PathBuf {
+    match relative.strip_prefix(PYTHON_MODS_PATH) {
+        | Ok(script) => PathBuf::from("scripts").join(script),
+        | Err(_) => relative.to_path_buf(),
+    }
+}
+
+fn is_compiled_only(file: &Path) -> bool {
+    match file.extension().and_then(|extension| extension.to_str()) {
+        | Some("pyc") => !file.with_extension("py").exists(),
+        | Some("swf") => true,
+        | _ => false,
+    }
+}
+
+pub fn import_archive(
+    archive_path: &PathBuf, directory: &PathBuf,
+) -> Result<ImportReport> {
+    let tmp_dir = tempdir()?;
+    let extract_path = tmp_dir.path().to_path_buf();
+    zip::extract(archive_path, &extract_path)?;
+
+    let meta_path = extract_path.join("meta.xml");
+    if !meta_path.exists() {
+        return Err(Error::MissingMetaError);
+    }
+    let mod_conf = ModConf::from_meta(&read_to_string(&meta_path)?)?;
+
+    let kebab_name = mod_conf
+        .name
+        .from_case(Case::Alternating)
+        .to_case(Case::Kebab);
+    let root_path = directory.join(&kebab_name);
+    if root_path.exists() {
+        return Err(Error::ExistingDirectoryError(root_path));
+    }
+
+    let mut report = ImportReport {
+        root_path: root_path.clone(),
+        ..Default::default()
+    };
+
+    let mut files = get_dir_content(extract_path.join("res"))
+        .map(|content| content.files)
+        .unwrap_or_default();
+    files.sort();
+
+    for file in files.into_iter().map(PathBuf::from) {
+        let Ok(relative) = file.strip_prefix(&extract_path) else {
+            continue;
+        };
+        let destination = project_path(relative);
+
+        if is_compiled_only(&file) {
+            report.compiled_only.push(destination.clone());
+        }
+
+        let destination = root_path.join(destination);
+        if let Some(parent) = destination.parent() {
+            create_dir_all(parent)?;
+        }
+        copy(&file, destination)?;
+    }
+
+    create_dir_all(root_path.join("scripts"))?;
+    mod_conf
+        .write_json_to_file(&root_path.join("mod.json"))
+        .map_err(|e| {
+            file_template::Error::FileCreateError(e, root_path.join("mod.json"))
+        })?;
+    init_git_repository(&root_path)?;
+
+    tmp_dir.close()?;
+
+    Ok(report)
+}
+
+pub fn print_report(report: &ImportReport) -> Result<()> {
+    for file in &report.compiled_only {
+        println!("Compiled only, no sources: {}", file.display());
+    }
+
+    let absolute_mod_path = convert_to_absolute_path(&report.root_path)?;
+    println!("Success! Imported at {absolute_mod_path}");
+
+    Ok(())
+}
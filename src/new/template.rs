@@ -22,6 +22,7 @@ fn template_mod_conf(args: &NewArgs, parent_dir: &PathBuf) -> Result<()> {
         version: args.version.clone(),
         name: args.name.clone(),
         description: args.description.clone(),
+        ..Default::default()
     };
     let file_path = &parent_dir.join("mod.json");
 
@@ -123,6 +124,7 @@ fn template_ui_config(args: &NewArgs, parent_dir: &PathBuf) -> Result<()> {
             output: "".to_string(),
             source_path: vec!["src".to_string()],
             library_path: lib,
+            define: vec![],
         },
         main_class: main_class_name,
     };
@@ -134,7 +136,7 @@ fn template_ui_config(args: &NewArgs, parent_dir: &PathBuf) -> Result<()> {
         .map_err(|e| file_template::Error::FileCreateError(e, filename))?)
 }
 
-fn init_git_repository(directory: &PathBuf) -> Result<()> {
+pub fn init_git_repository(directory: &PathBuf) -> Result<()> {
     template_git_ignore(directory)?;
 
     git2::Repository::init(directory)?;
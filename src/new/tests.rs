@@ -92,6 +92,7 @@ def fini():
                 output: "".to_string(),
                 source_path: vec!["src".to_string()],
                 library_path: lib_content_path,
+                define: vec![],
             },
             main_class: "fr.gabouchet.BetterMatchmaking".to_string(),
         };
@@ -115,3 +116,62 @@ proxy: none\n"
         tmp_dir.close().unwrap();
     }
 }
+
+#[cfg(test)]
+mod import_tests {
+    use crate::new::import::import_archive;
+    use crate::utils::zip::archive_directories;
+    use std::fs::{create_dir_all, read_to_string, write};
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn import_wotmod() {
+        let tmp_dir = tempdir().unwrap();
+        let build_path = tmp_dir.path().join("build");
+        let mods_path = build_path.join("res/scripts/client/gui/mods");
+        create_dir_all(&mods_path).unwrap();
+        create_dir_all(build_path.join("res/gui/flash")).unwrap();
+        write(
+            build_path.join("meta.xml"),
+            "<root><id>fr.gabouchet.better-matchmaking</id><version>1.0.2</version><name>Better matchmaking</name><description>Best mod ever</description></root>",
+        )
+        .unwrap();
+        write(mods_path.join("mod_better_matchmaking.pyc"), [0; 8]).unwrap();
+        write(mods_path.join("helper.py"), "def help(): pass\n").unwrap();
+        write(mods_path.join("helper.pyc"), [0; 8]).unwrap();
+        write(build_path.join("res/gui/flash/settings.swf"), [0; 8]).unwrap();
+        write(build_path.join("res/gui/settings.xml"), "<settings/>").unwrap();
+
+        let archive_path = tmp_dir.path().join("mod.wotmod");
+        archive_directories(&archive_path, &[build_path]).unwrap();
+
+        let projects_path = tmp_dir.path().join("projects");
+        create_dir_all(&projects_path).unwrap();
+        let report = import_archive(&archive_path, &projects_path).unwrap();
+
+        let mod_path = projects_path.join("better-matchmaking");
+        assert_eq!(report.root_path, mod_path);
+        assert_eq!(
+            report.compiled_only,
+            vec![
+                PathBuf::from("res/gui/flash/settings.swf"),
+                PathBuf::from("scripts/mod_better_matchmaking.pyc"),
+            ]
+        );
+        assert_eq!(
+            read_to_string(mod_path.join("mod.json")).unwrap(),
+            "{
+  \"id\": \"fr.gabouchet.better-matchmaking\",
+  \"version\": \"1.0.2\",
+  \"name\": \"Better matchmaking\",
+  \"description\": \"Best mod ever\"
+}"
+        );
+        assert!(mod_path.join("scripts/helper.py").exists());
+        assert!(mod_path.join("res/gui/settings.xml").exists());
+        assert!(mod_path.join(".gitignore").exists());
+
+        assert!(import_archive(&archive_path, &projects_path).is_err());
+    }
+}
@@ -52,7 +52,7 @@ pub fn load_as3(as3_path: &PathBuf) -> Result<AS3> {
     let as3 = AS3::from(as3_path);
 
     if !as3.is_installed() {
-        println!("Installing action script SDK...");
+        eprintln!("Installing action script SDK...");
         as3.install().map_err(|e| Error::InstallError(e))?;
     }
 
@@ -1,10 +1,11 @@
-use crate::config::Configs;
 use crate::sdk::npm::NPM;
 use crate::sdk::nvm::BoxedNVM;
 use crate::sdk::{npm, nvm, InstallResult, Installable};
-use crate::utils::command::{self, command};
+use crate::utils::command::{self, output_unless_cancelled};
 use std::path::PathBuf;
+use std::process::Command;
 use std::string::FromUtf8Error;
+use std::sync::atomic::AtomicBool;
 use std::{process, result};
 
 #[derive(thiserror::Error, Debug)]
@@ -27,9 +28,6 @@ pub enum Error {
     #[error("Unable to decode output of the command")]
     DecodeOutputError(#[from] FromUtf8Error),
 
-    #[error("Failed to build flash")]
-    BuildError(String),
-
     #[error("Failed to convert")]
     ConvertionError(String),
 }
@@ -67,7 +65,9 @@ impl From<NPM> for ASConfigc {
 }
 
 impl ASConfigc {
-    fn exec(&self, args: Vec<&str>) -> Result<process::Output> {
+    fn exec(
+        &self, args: Vec<&str>, cancel: &AtomicBool,
+    ) -> Result<process::Output> {
         let bin_dir = self.npm.get_bin_directory()?;
         let mut args_override = args.clone();
         let exec_path: PathBuf;
@@ -79,9 +79,11 @@ impl ASConfigc {
             exec_path = bin_dir.join("asconfigc");
         };
 
-        let executable = exec_path.as_os_str();
-        let output = command(executable, args_override, vec![])
-            .map_err(Error::ExecutionError)?;
+        let output = output_unless_cancelled(
+            Command::new(&exec_path).args(args_override),
+            cancel,
+        )
+        .map_err(Error::ExecutionError)?;
 
         if !output.status.success() {
             return Err(Error::BadExitStatus(output));
@@ -90,10 +92,10 @@ impl ASConfigc {
         Ok(output)
     }
 
-    pub fn build(&self, input_path: &PathBuf) -> Result<()> {
-        let config =
-            Configs::load().map_err(|e| Error::BuildError(e.to_string()))?;
-        let as3_sdk_path = config.as3.get_as3_path();
+    pub fn build(
+        &self, as3_sdk_path: &PathBuf, input_path: &PathBuf,
+        cancel: &AtomicBool,
+    ) -> Result<process::Output> {
         let as3_sdk_path_string = as3_sdk_path.to_str().ok_or(
             Error::ConvertionError("as3_sdk_path to string".to_string()),
         )?;
@@ -102,18 +104,14 @@ impl ASConfigc {
             Error::ConvertionError("input_path to string".to_string()),
         )?;
 
-        let _ = self.exec(vec![
-            "--sdk",
-            as3_sdk_path_string,
-            "-p",
-            input_path_string,
-        ])?;
-
-        Ok(())
+        self.exec(
+            vec!["--sdk", as3_sdk_path_string, "-p", input_path_string],
+            cancel,
+        )
     }
 
     pub fn version(&self) -> Result<String> {
-        let out = self.exec(vec!["--version"])?;
+        let out = self.exec(vec!["--version"], &AtomicBool::new(false))?;
         let version = String::from_utf8(out.stdout)?.trim().to_string();
 
         Ok(version)
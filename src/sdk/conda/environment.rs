@@ -1,21 +1,20 @@
+use crate::utils::command;
+use crate::utils::command::output_unless_cancelled;
 use std::path::PathBuf;
 use std::process::{Command, Output};
 use std::result;
-use std::str::Utf8Error;
+use std::sync::atomic::AtomicBool;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Can't invoke command: {0}")]
     CommandInvocationError(PathBuf),
 
-    #[error("Command error")]
-    CommandError(Output),
-
-    #[error("Cannot read the command output")]
-    CommandOutputParsingError(#[from] Utf8Error),
-
     #[error("Unable to reads sources directory")]
     PathError,
+
+    #[error("Compilation cancelled")]
+    CancelledError,
 }
 
 type Result<T> = result::Result<T, Error>;
@@ -33,37 +32,39 @@ impl From<PathBuf> for CondaEnvironment {
 }
 
 impl CondaEnvironment {
-    pub fn compile_all(&self, directory: &PathBuf) -> Result<()> {
+    // compileall reports syntax errors on its output, the caller parses them.
+    // Optimized builds write .pyo files, the display path replaces the
+    // directory in the co_filename of the compiled code
+    pub fn compile_all(
+        &self, directory: &PathBuf, optimize: bool, display_path: &str,
+        cancel: &AtomicBool,
+    ) -> Result<Output> {
         let python_src = directory.to_str().ok_or(Error::PathError)?;
 
-        self.python(vec!["-m", "compileall", python_src])?;
-
-        Ok(())
-    }
+        let mut args = vec![];
+        if optimize {
+            args.push("-OO");
+        }
+        args.extend(["-m", "compileall", "-d", display_path, python_src]);
 
-    fn python(&self, args: Vec<&str>) -> Result<(String, String)> {
-        self.command("python", args)
+        self.run("python", args, cancel)
     }
 
-    fn command(
-        &self, executable_name: &str, args: Vec<&str>,
-    ) -> Result<(String, String)> {
+    fn run(
+        &self, executable_name: &str, args: Vec<&str>, cancel: &AtomicBool,
+    ) -> Result<Output> {
         let executable_path = self.get_executable_path(executable_name);
-        let mut command = Command::new(&executable_path);
-
-        let output = command
-            .args(args)
-            .output()
-            .map_err(|_| Error::CommandInvocationError(executable_path))?;
-
-        if !output.status.success() {
-            return Err(Error::CommandError(output));
-        }
-
-        let stdout = std::str::from_utf8(&output.stdout)?.to_string();
-        let stderr = std::str::from_utf8(&output.stderr)?.to_string();
 
-        Ok((stdout, stderr))
+        output_unless_cancelled(
+            Command::new(&executable_path).args(args),
+            cancel,
+        )
+        .map_err(|e| match e {
+            | command::Error::CancelledError => Error::CancelledError,
+            | command::Error::ExecutionError(_) => {
+                Error::CommandInvocationError(executable_path)
+            },
+        })
     }
 
     fn get_executable_path(&self, name: &str) -> PathBuf {
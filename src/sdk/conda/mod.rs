@@ -144,7 +144,7 @@ pub fn load_conda(conda_path: &PathBuf) -> Result<Conda> {
     let conda = Conda::from(conda_path);
 
     if !conda.is_installed() {
-        println!("Installing conda...");
+        eprintln!("Installing conda...");
         conda.install().expect("failed conda installation");
     }
 
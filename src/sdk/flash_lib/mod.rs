@@ -5,6 +5,7 @@ use crate::utils::extract_archive::extract_archive;
 use regex::Regex;
 use std::fs::{create_dir_all, read_dir, remove_dir_all};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tempfile::tempdir;
 
 #[derive(thiserror::Error, Debug)]
@@ -13,10 +14,10 @@ pub(crate) enum Error {
     ExtractError(#[from] extract_archive::Error),
     #[error("Failed to build lib: {0}")]
     BuildError(String),
-    #[error("Value is null: {0}")]
-    NullError(String),
     #[error("Convertion failed: {0}")]
     PatternError(#[from] regex::Error),
+    #[error("Extraction cancelled")]
+    CancelledError,
 }
 
 pub struct GameFlashLib {
@@ -43,16 +44,16 @@ impl GameFlashLib {
         self.game_flash_lib.exists()
     }
 
-    fn extract(&self) -> Result<(), Error> {
-        println!("Building game flash lib...");
+    fn extract(&self, cancel: &AtomicBool) -> Result<(), Error> {
+        eprintln!("Building game flash lib...");
         let tmp_dir =
             tempdir().map_err(|e| Error::BuildError(e.to_string()))?;
 
-        let settings =
+        let mut settings =
             load_settings().map_err(|e| Error::BuildError(e.to_string()))?;
         let game_client_path = settings
-            .game_client_path
-            .ok_or(Error::NullError("game_client_path".to_string()))?;
+            .require_game_client_path()
+            .map_err(|e| Error::BuildError(e.to_string()))?;
 
         let package_path = game_client_path.join("res/packages/");
         let archive_list = read_dir(package_path)
@@ -60,6 +61,11 @@ impl GameFlashLib {
 
         let pattern = Regex::new(r"^.*gui-part[0-9].pkg?")?;
         for archive in archive_list.flatten() {
+            // Each package is large, stop between them once cancelled
+            if cancel.load(Ordering::SeqCst) {
+                return Err(Error::CancelledError);
+            }
+
             let dir_item_path = archive.path();
             let str = dir_item_path.to_str().ok_or(Error::BuildError(
                 "failed to convert path to string".to_string(),
@@ -70,6 +76,10 @@ impl GameFlashLib {
             }
         }
 
+        if cancel.load(Ordering::SeqCst) {
+            return Err(Error::CancelledError);
+        }
+
         let inside_archive_path =
             tmp_dir.path().to_path_buf().join("gui/flash/swc/");
         copy_directory(&inside_archive_path, &self.game_flash_lib)
@@ -81,7 +91,7 @@ impl GameFlashLib {
 }
 
 pub fn extract_flash_client_lib(
-    wg_mod_home: &PathBuf,
+    wg_mod_home: &PathBuf, cancel: &AtomicBool,
 ) -> Result<GameFlashLib, Error> {
     let game_flash_lib_path = wg_mod_home.join("flash_lib");
     let game_flash_lib = GameFlashLib::from(game_flash_lib_path);
@@ -95,7 +105,7 @@ pub fn extract_flash_client_lib(
         .map_err(|e| Error::BuildError(e.to_string()))?;
 
     game_flash_lib
-        .extract()
+        .extract(cancel)
         .map_err(|e| Error::BuildError(e.to_string()))?;
 
     Ok(game_flash_lib)
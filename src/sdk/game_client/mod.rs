@@ -1,11 +1,429 @@
-use std::path::PathBuf;
+use crate::config::mod_conf;
+use crate::config::mod_conf::ModConf;
+use crate::utils::zip;
+use semver::{Version, VersionReq};
+use std::fs::{
+    copy, create_dir_all, read_dir, read_to_string, remove_file, rename,
+};
+use std::path::{Path, PathBuf};
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to read the game client version file {0}\n{1}")]
+    VersionFileError(PathBuf, io::Error),
+
+    #[error("Unable to parse the game client version file\n{0}")]
+    VersionParseError(#[from] roxmltree::Error),
+
+    #[error("No version found in the game client version file")]
+    MissingVersionError,
+
+    #[error("Unable to read the mod metadata of {0}\n{1}")]
+    ModMetaError(PathBuf, String),
+
+    #[error("Unable to install the mod\n{0}")]
+    InstallError(#[from] io::Error),
+
+    #[error("Missing dependencies in {0:?}: {1}\nInstalled versions that aren't numbers like 1.2.3 only match \"*\"")]
+    MissingDependencyError(PathBuf, String),
+
+    #[error("Conflicting mods installed: {0}")]
+    ConflictError(String),
+}
+
+type Result<T> = result::Result<T, Error>;
 
 pub struct GameClient {
     pub path: PathBuf,
 }
 
+pub struct InstallReport {
+    pub installed: PathBuf,
+    pub replaced: Vec<PathBuf>,
+}
+
 impl From<&PathBuf> for GameClient {
     fn from(path: &PathBuf) -> Self {
         GameClient { path: path.clone() }
     }
 }
+
+// Mods are often organised in subfolders of mods/<version>
+fn find_archives(directory: &Path, archives: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in read_dir(directory)?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_archives(&path, archives)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "wotmod")
+        {
+            archives.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+// Mods not built with wg-mod often have versions like 1.2
+fn parse_version(version: &str) -> Option<Version> {
+    if let Ok(version) = Version::parse(version) {
+        return Some(version);
+    }
+
+    let mut parts = version.trim().split('.').map(str::parse::<u64>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some(Version::new(major, minor, patch))
+}
+
+pub fn read_archive_meta(archive_path: &PathBuf) -> Result<ModConf> {
+    let to_error = |e: String| Error::ModMetaError(archive_path.clone(), e);
+
+    let content = zip::read_entry(archive_path, "meta.xml")
+        .map_err(|e: zip::Error| to_error(e.to_string()))?;
+    let content = String::from_utf8_lossy(&content);
+
+    ModConf::from_meta(&content)
+        .map_err(|e: mod_conf::Error| to_error(e.to_string()))
+}
+
+fn same_file_path(path: &PathBuf) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.clone())
+}
+
+impl GameClient {
+    pub fn version(&self) -> Result<String> {
+        let version_file = self.path.join("version.xml");
+        let content = read_to_string(&version_file)
+            .map_err(|e| Error::VersionFileError(version_file.clone(), e))?;
+        let document = roxmltree::Document::parse(&content)?;
+
+        let version = document
+            .descendants()
+            .find(|node| node.has_tag_name("version"))
+            .and_then(|node| node.text())
+            .ok_or(Error::MissingVersionError)?;
+
+        // The client stores something like "v.1.26.0.1 #1234"
+        let version = version
+            .trim()
+            .trim_start_matches("v.")
+            .split_whitespace()
+            .next()
+            .ok_or(Error::MissingVersionError)?;
+
+        Ok(version.to_string())
+    }
+
+    pub fn mods_path(&self) -> Result<PathBuf> {
+        Ok(self.path.join("mods").join(self.version()?))
+    }
+
+    // Archives that can't be read are ignored, the game would skip them too
+    pub fn installed_mods(&self) -> Result<Vec<(PathBuf, ModConf)>> {
+        let mods_path = self.mods_path()?;
+        if !mods_path.is_dir() {
+            return Ok(vec![]);
+        }
+
+        let mut archives = vec![];
+        find_archives(&mods_path, &mut archives)?;
+
+        let mut mods = archives
+            .into_iter()
+            .filter_map(|path| {
+                read_archive_meta(&path).ok().map(|meta| (path, meta))
+            })
+            .collect::<Vec<_>>();
+        mods.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(mods)
+    }
+
+    fn check_requirements(
+        &self, mod_conf: &ModConf, installed: &[&ModConf],
+    ) -> Result<()> {
+        let matches = |id: &String, range: &String| {
+            let requirement = VersionReq::parse(range).ok();
+            installed.iter().any(|meta| {
+                &meta.package_name == id
+                    && match (&requirement, parse_version(&meta.version)) {
+                        | (Some(requirement), Some(version)) => {
+                            requirement.matches(&version)
+                        },
+                        | _ => range == "*",
+                    }
+            })
+        };
+
+        let missing = mod_conf
+            .dependencies
+            .iter()
+            .filter(|(id, range)| !matches(id, range))
+            .map(|(id, range)| format!("{id} {range}"))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(Error::MissingDependencyError(
+                self.mods_path()?,
+                missing.join(", "),
+            ));
+        }
+
+        let conflicts = mod_conf
+            .conflicts
+            .iter()
+            .filter(|(id, range)| matches(id, range))
+            .map(|(id, range)| format!("{id} {range}"))
+            .collect::<Vec<_>>();
+        if !conflicts.is_empty() {
+            return Err(Error::ConflictError(conflicts.join(", ")));
+        }
+
+        Ok(())
+    }
+
+    pub fn install_mod(&self, archive_path: &PathBuf) -> Result<InstallReport> {
+        let mod_conf = read_archive_meta(archive_path)?;
+        mod_conf.validate_file_name_fields().map_err(|e| {
+            Error::ModMetaError(archive_path.clone(), e.to_string())
+        })?;
+
+        let installed_mods = self.installed_mods()?;
+        let (same_package, other_mods): (Vec<_>, Vec<_>) = installed_mods
+            .iter()
+            .partition(|(_, meta)| meta.package_name == mod_conf.package_name);
+        let other_mods =
+            other_mods.iter().map(|(_, meta)| meta).collect::<Vec<_>>();
+        self.check_requirements(&mod_conf, &other_mods)?;
+
+        let mods_path = self.mods_path()?;
+        create_dir_all(&mods_path)?;
+
+        // The new build is in place before older ones are removed, so a
+        // failed copy keeps the installed mod
+        let file_name =
+            format!("{}_{}.wotmod", mod_conf.package_name, mod_conf.version);
+        let installed = mods_path.join(&file_name);
+        let source = same_file_path(archive_path);
+        if source != same_file_path(&installed) {
+            let partial = mods_path.join(format!(".{file_name}.partial"));
+            copy(archive_path, &partial)?;
+            rename(&partial, &installed)?;
+        }
+
+        let kept = [source, same_file_path(&installed)];
+        let mut replaced = vec![];
+        for (path, _) in same_package {
+            if kept.contains(&same_file_path(path)) {
+                continue;
+            }
+            remove_file(path)?;
+            replaced.push(path.clone());
+        }
+
+        Ok(InstallReport {
+            installed,
+            replaced,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::mock_mod;
+    use std::fs::write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn install_mod_in_fake_client() {
+        let tmp_dir = tempdir().unwrap();
+        let client_path = tmp_dir.path().join("World_of_Tanks_EU");
+        let mods_path = client_path.join("mods/1.26.0.1");
+        create_dir_all(&mods_path).unwrap();
+        write(
+            client_path.join("version.xml"),
+            "<version.xml>\n\t<version>\tv.1.26.0.1 #1234</version>\n</version.xml>",
+        )
+        .unwrap();
+
+        let tmp_path = tmp_dir.path().to_path_buf();
+        let old_build =
+            mock_mod(&mods_path.join("old.wotmod"), "com.example.mod", "1.0.0");
+        let other_mod =
+            mock_mod(&mods_path.join("other.wotmod"), "com.other.mod", "1.0.0");
+        let archive = mock_mod(
+            &tmp_path.join("result.wotmod"),
+            "com.example.mod",
+            "1.0.0",
+        );
+
+        let game_client = GameClient::from(&client_path);
+        assert_eq!(game_client.version().unwrap(), "1.26.0.1");
+
+        let report = game_client.install_mod(&archive).unwrap();
+
+        assert_eq!(
+            report.installed,
+            mods_path.join("com.example.mod_1.0.0.wotmod")
+        );
+        assert_eq!(report.replaced, vec![old_build.clone()]);
+        assert!(report.installed.exists());
+        assert!(!old_build.exists());
+        assert!(other_mod.exists());
+
+        tmp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn check_dependencies_before_install() {
+        let tmp_dir = tempdir().unwrap();
+        let client_path = tmp_dir.path().join("World_of_Tanks_EU");
+        let mods_path = client_path.join("mods/1.26.0.1");
+        create_dir_all(&mods_path).unwrap();
+        write(
+            client_path.join("version.xml"),
+            "<version.xml><version>v.1.26.0.1</version></version.xml>",
+        )
+        .unwrap();
+        let game_client = GameClient::from(&client_path);
+
+        let library = ModConf {
+            package_name: "com.example.lib".to_string(),
+            version: "1.3.0".to_string(),
+            ..Default::default()
+        };
+        let mut mod_conf = ModConf {
+            package_name: "com.example.mod".to_string(),
+            version: "1.0.0".to_string(),
+            ..Default::default()
+        };
+        mod_conf
+            .dependencies
+            .insert("com.example.lib".to_string(), "^1.2".to_string());
+
+        assert!(game_client.check_requirements(&mod_conf, &[]).is_err());
+        assert!(game_client
+            .check_requirements(&mod_conf, &[&library])
+            .is_ok());
+
+        mod_conf
+            .dependencies
+            .insert("com.example.lib".to_string(), "^2".to_string());
+        assert!(game_client
+            .check_requirements(&mod_conf, &[&library])
+            .is_err());
+
+        let library = ModConf {
+            version: "2.1".to_string(),
+            ..library
+        };
+        assert!(game_client
+            .check_requirements(&mod_conf, &[&library])
+            .is_ok());
+
+        mod_conf.dependencies.clear();
+        mod_conf
+            .conflicts
+            .insert("com.example.lib".to_string(), "*".to_string());
+        assert!(game_client
+            .check_requirements(&mod_conf, &[&library])
+            .is_err());
+
+        tmp_dir.close().unwrap();
+    }
+
+    #[test]
+    fn find_mods_in_subfolders() {
+        let tmp_dir = tempdir().unwrap();
+        let client_path = tmp_dir.path().join("World_of_Tanks_EU");
+        let mods_path = client_path.join("mods/1.26.0.1");
+        create_dir_all(mods_path.join("author/libs")).unwrap();
+        write(
+            client_path.join("version.xml"),
+            "<version.xml><version>v.1.26.0.1</version></version.xml>",
+        )
+        .unwrap();
+
+        let nested = mock_mod(
+            &mods_path.join("author/libs/com.example.lib.wotmod"),
+            "com.example.lib",
+            "1.2",
+        );
+        let top_level = mock_mod(
+            &mods_path.join("com.example.mod.wotmod"),
+            "com.example.mod",
+            "1.0.0",
+        );
+
+        let installed = GameClient::from(&client_path)
+            .installed_mods()
+            .unwrap()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+        assert_eq!(installed, vec![nested, top_level]);
+    }
+
+    #[test]
+    fn refuse_archive_with_a_path_as_id() {
+        let tmp_dir = tempdir().unwrap();
+        let client_path = tmp_dir.path().join("World_of_Tanks_EU");
+        let mods_path = client_path.join("mods/1.26.0.1");
+        create_dir_all(&mods_path).unwrap();
+        write(
+            client_path.join("version.xml"),
+            "<version.xml><version>v.1.26.0.1</version></version.xml>",
+        )
+        .unwrap();
+
+        let archive =
+            mock_mod(&tmp_dir.path().join("evil.wotmod"), "../evil", "1.0.0");
+        let result = GameClient::from(&client_path).install_mod(&archive);
+
+        assert!(matches!(result, Err(Error::ModMetaError(..))));
+        assert!(!client_path.join("mods/evil_1.0.0.wotmod").exists());
+        assert_eq!(read_dir(&mods_path).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn reinstall_archive_from_mods_folder() {
+        let tmp_dir = tempdir().unwrap();
+        let client_path = tmp_dir.path().join("World_of_Tanks_EU");
+        let mods_path = client_path.join("mods/1.26.0.1");
+        create_dir_all(&mods_path).unwrap();
+        write(
+            client_path.join("version.xml"),
+            "<version.xml><version>v.1.26.0.1</version></version.xml>",
+        )
+        .unwrap();
+        let game_client = GameClient::from(&client_path);
+
+        let installed = mock_mod(
+            &mods_path.join("com.example.mod_1.0.0.wotmod"),
+            "com.example.mod",
+            "1.0.0",
+        );
+        let report = game_client.install_mod(&installed).unwrap();
+        assert_eq!(report.installed, installed);
+        assert!(report.replaced.is_empty());
+        assert!(installed.exists());
+
+        let renamed = mods_path.join("my_build.wotmod");
+        std::fs::rename(&installed, &renamed).unwrap();
+        let report = game_client.install_mod(&renamed).unwrap();
+        assert_eq!(report.installed, installed);
+        assert!(report.replaced.is_empty());
+        assert!(installed.exists());
+        assert!(renamed.exists());
+
+        tmp_dir.close().unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 use crate::utils::convert_to_absolute_path::convert_to_absolute_path;
+use crate::utils::interactive;
 use fs_extra::dir::get_dir_content;
 use git2::{
     Branch, BranchType, FetchOptions, Remote, RemoteCallbacks, Repository,
@@ -7,6 +8,7 @@ use inquire::Select;
 use std::fs::create_dir_all;
 use std::io::Write;
 use std::path::{PathBuf, MAIN_SEPARATOR};
+use std::sync::{Mutex, MutexGuard};
 use std::{io, result};
 
 #[derive(thiserror::Error, Debug)]
@@ -28,12 +30,16 @@ pub enum Error {
 
     #[error("Unable to walk in the directory")]
     FilesystemError(#[from] fs_extra::error::Error),
+
+    #[error("{0}")]
+    NonInteractiveError(#[from] interactive::Error),
 }
 
 type Result<T> = result::Result<T, Error>;
 
+// git2 repositories can't be shared between threads as is
 pub struct GameSources {
-    repository: Repository,
+    repository: Mutex<Repository>,
 }
 
 fn fetch(remote: &mut Remote) -> Result<()> {
@@ -45,11 +51,11 @@ fn fetch(remote: &mut Remote) -> Result<()> {
             / stats.total_deltas() as f32;
 
         if stats.received_objects() != stats.total_objects() {
-            print!("Fetching WoT sources ... {:.0}%\r", download_progress);
+            eprint!("Fetching WoT sources ... {:.0}%\r", download_progress);
         } else {
-            print!("Unpacking WoT sources ... {:.0}%\r", unzip_progress);
+            eprint!("Unpacking WoT sources ... {:.0}%\r", unzip_progress);
         }
-        io::stdout().flush().ok();
+        io::stderr().flush().ok();
         true
     });
 
@@ -99,9 +105,7 @@ impl GameSources {
 
         fetch(&mut remote)?;
 
-        let game_sources = GameSources {
-            repository: get_repository(&path, false)?,
-        };
+        let game_sources = GameSources::open(path)?;
 
         if !already_exists {
             game_sources.prompt_channel()?;
@@ -110,9 +114,23 @@ impl GameSources {
         Ok(game_sources)
     }
 
+    // Opens an existing checkout without fetching it
+    pub fn open(path: &PathBuf) -> Result<Self> {
+        Ok(GameSources {
+            repository: Mutex::new(get_repository(path, false)?),
+        })
+    }
+
+    fn repository(&self) -> MutexGuard<'_, Repository> {
+        self.repository
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     fn list_channels(&self) -> Result<Vec<String>> {
         let branches_options = Some(BranchType::Remote);
-        let it = self.repository.branches(branches_options)?;
+        let repository = self.repository();
+        let it = repository.branches(branches_options)?;
         let mut branches: Vec<String> = vec![];
 
         let branches_result: Vec<
@@ -131,6 +149,8 @@ impl GameSources {
     }
 
     pub fn prompt_channel(&self) -> Result<()> {
+        interactive::ensure_interactive("WoT development channel")?;
+
         let channels_available = self.list_channels()?;
         let channel_selected = Select::new(
             "Select a World of Tanks development channel:",
@@ -145,22 +165,23 @@ impl GameSources {
 
     fn switch_channel(&self, channel_name: &str) -> Result<()> {
         let branch_name = format!("origin/{channel_name}");
-        let (object, reference) = self.repository.revparse_ext(&branch_name)?;
+        let repository = self.repository();
+        let (object, reference) = repository.revparse_ext(&branch_name)?;
 
-        self.repository.checkout_tree(&object, None)?;
+        repository.checkout_tree(&object, None)?;
         match reference {
-            | Some(reference) => self
-                .repository
+            | Some(reference) => repository
                 .set_head(reference.name().ok_or(Error::GitBranchError)?),
-            | None => self.repository.set_head_detached(object.id()),
+            | None => repository.set_head_detached(object.id()),
         }?;
 
         Ok(())
     }
 
     pub fn get_channel(&self) -> Result<String> {
-        let current_commit = self.repository.head()?.peel_to_commit()?;
-        let references = self.repository.references()?;
+        let repository = self.repository();
+        let current_commit = repository.head()?.peel_to_commit()?;
+        let references = repository.references()?;
 
         for reference_pack in references {
             if let Ok(reference) = reference_pack {
@@ -182,6 +203,13 @@ impl GameSources {
         Err(Error::GitBranchError)
     }
 
+    pub fn revision(&self) -> Result<String> {
+        let repository = self.repository();
+        let commit = repository.head()?.peel_to_commit()?;
+
+        Ok(commit.id().to_string())
+    }
+
     fn list_directory_paths(&self, path: &PathBuf) -> Result<Vec<String>> {
         let directory_content = get_dir_content(path)?;
         let folders = directory_content.directories;
@@ -205,8 +233,9 @@ impl GameSources {
     }
 
     pub fn list_python_root_modules(&self) -> Result<Vec<String>> {
+        let repository = self.repository();
         let sources_path =
-            self.repository.path().parent().ok_or(Error::PathError)?;
+            repository.path().parent().ok_or(Error::PathError)?;
         let python_sources_path = sources_path.join("sources/res");
         let sub_paths = self.list_directory_paths(&python_sources_path)?;
 
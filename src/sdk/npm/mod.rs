@@ -62,7 +62,7 @@ impl NPM {
     }
 
     pub fn install_package(&self, name: &str) -> Result<()> {
-        println!("Installing {}...", name);
+        eprintln!("Installing {}...", name);
 
         let result = self
             .exec(vec!["install", "-g", name])
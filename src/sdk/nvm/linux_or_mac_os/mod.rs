@@ -61,7 +61,7 @@ impl Installable for LinuxOrMacOsNVM {
 
 impl NVM for LinuxOrMacOsNVM {
     fn install_node(&self) -> nvm::Result<()> {
-        println!("Installing Node via nvm...");
+        eprintln!("Installing Node via nvm...");
 
         self.exec(vec!["install", "node"])?;
 
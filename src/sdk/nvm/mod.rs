@@ -82,7 +82,7 @@ pub fn load_nvm(nvm_path: &PathBuf) -> Result<BoxedNVM> {
     };
 
     if !nvm_installer.is_installed() {
-        println!("Install nvm ...");
+        eprintln!("Install nvm ...");
         nvm_installer
             .install()
             .map_err(|e| Error::InstallError(e))?;
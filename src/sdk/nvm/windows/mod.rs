@@ -45,7 +45,7 @@ impl Installable for WindowsNVM {
 
 impl NVM for WindowsNVM {
     fn install_node(&self) -> nvm::Result<()> {
-        println!("Installing Node via nvm...");
+        eprintln!("Installing Node via nvm...");
 
         let args = vec!["install", "latest"];
         self.exec(args)
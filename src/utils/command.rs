@@ -1,16 +1,25 @@
 use crate::utils::Env;
 use std::ffi::OsStr;
-use std::process::{Command, Output};
+use std::io::Read;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
 use std::{io, result};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Failed to execute command")]
     ExecutionError(#[from] io::Error),
+
+    #[error("Command cancelled")]
+    CancelledError,
 }
 
 type Result<T> = result::Result<T, Error>;
 
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 pub fn command<S: AsRef<OsStr>>(
     command: S, args: Vec<&str>, env: Vec<Env>,
 ) -> Result<Output> {
@@ -25,3 +34,120 @@ pub fn command<S: AsRef<OsStr>>(
 
     Ok(out)
 }
+
+fn read_pipe<R: Read + Send + 'static>(
+    pipe: Option<R>,
+) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut content = vec![];
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut content).ok();
+        }
+        content
+    })
+}
+
+// The child leads its own group, so cancelling also reaches the processes
+// it starts, like the java compiler behind asconfigc
+#[cfg(unix)]
+fn spawn_process_group(command: &mut Command) -> &mut Command {
+    use std::os::unix::process::CommandExt;
+    command.process_group(0)
+}
+
+#[cfg(windows)]
+fn spawn_process_group(command: &mut Command) -> &mut Command {
+    command
+}
+
+#[cfg(unix)]
+fn kill_process_group(child: &mut Child) {
+    let group = format!("-{}", child.id());
+    Command::new("kill")
+        .args(["-KILL", "--", &group])
+        .output()
+        .ok();
+    child.kill().ok();
+}
+
+#[cfg(windows)]
+fn kill_process_group(child: &mut Child) {
+    let pid = child.id().to_string();
+    Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid])
+        .output()
+        .ok();
+    child.kill().ok();
+}
+
+// Same as Command::output, but the process and its children are killed as
+// soon as cancel is set by another thread
+pub fn output_unless_cancelled(
+    command: &mut Command, cancel: &AtomicBool,
+) -> Result<Output> {
+    let mut child = spawn_process_group(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Output {
+                status,
+                stdout: stdout.join().unwrap_or_default(),
+                stderr: stderr.join().unwrap_or_default(),
+            });
+        }
+
+        if cancel.load(Ordering::SeqCst) {
+            kill_process_group(&mut child);
+            child.wait()?;
+            stdout.join().ok();
+            stderr.join().ok();
+            return Err(Error::CancelledError);
+        }
+
+        thread::sleep(CANCEL_POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    #[test]
+    fn cancel_running_command() {
+        let cancel = AtomicBool::new(false);
+        let output = output_unless_cancelled(
+            Command::new("sh").args(["-c", "echo done"]),
+            &cancel,
+        )
+        .unwrap();
+        assert_eq!(output.stdout, b"done\n");
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let started = Instant::now();
+        let canceller = {
+            let cancel = cancel.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(100));
+                cancel.store(true, Ordering::SeqCst);
+            })
+        };
+        // The readers only finish once the backgrounded sleep is killed too
+        let result = output_unless_cancelled(
+            Command::new("sh").args(["-c", "sleep 10 & wait"]),
+            &cancel,
+        );
+        canceller.join().unwrap();
+
+        assert!(matches!(result, Err(Error::CancelledError)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -52,6 +52,18 @@ where
         .map_err(Error::TemplateWriteError)
 }
 
+pub fn render_template<T>(template: &str, data: &T) -> Result<String>
+where
+    T: Serialize,
+{
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+
+    handlebars
+        .render_template(template, data)
+        .map_err(Error::TemplateWriteError)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
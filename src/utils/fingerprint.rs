@@ -0,0 +1,100 @@
+use sha2::{Digest, Sha256};
+use std::fs::{read, read_dir};
+use std::path::PathBuf;
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to read {0} to compute its fingerprint\n{1}")]
+    ReadError(PathBuf, io::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub struct Fingerprint {
+    hasher: Sha256,
+}
+
+impl Fingerprint {
+    pub fn new() -> Self {
+        Self {
+            hasher: Sha256::new(),
+        }
+    }
+
+    pub fn add_str(&mut self, value: &str) {
+        self.hasher.update(value.len().to_le_bytes());
+        self.hasher.update(value.as_bytes());
+    }
+
+    pub fn add_path(&mut self, path: &PathBuf) -> Result<()> {
+        self.add_entry(path, path)
+    }
+
+    fn add_entry(&mut self, root: &PathBuf, path: &PathBuf) -> Result<()> {
+        let relative_path = path.strip_prefix(root).unwrap_or(path);
+        self.add_str(&relative_path.to_string_lossy());
+
+        if path.is_file() {
+            let content =
+                read(path).map_err(|e| Error::ReadError(path.clone(), e))?;
+            self.hasher.update(content.len().to_le_bytes());
+            self.hasher.update(&content);
+        } else if path.is_dir() {
+            let mut entries = read_dir(path)
+                .map_err(|e| Error::ReadError(path.clone(), e))?
+                .flatten()
+                .map(|entry| entry.path())
+                .collect::<Vec<_>>();
+            entries.sort();
+
+            for entry in entries {
+                self.add_entry(root, &entry)?;
+            }
+        } else {
+            self.add_str("<missing>");
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(self) -> String {
+        format!("{:x}", self.hasher.finalize())
+    }
+}
+
+pub fn sha256_file(path: &PathBuf) -> Result<String> {
+    let content = read(path).map_err(|e| Error::ReadError(path.clone(), e))?;
+
+    Ok(format!("{:x}", Sha256::digest(&content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, write};
+    use tempfile::tempdir;
+
+    fn fingerprint_of(path: &PathBuf) -> String {
+        let mut fingerprint = Fingerprint::new();
+        fingerprint.add_path(path).unwrap();
+        fingerprint.finish()
+    }
+
+    #[test]
+    fn fingerprint_follows_directory_content() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path().join("scripts");
+        create_dir_all(root.join("sub")).unwrap();
+        write(root.join("mod_a.py"), "print('a')").unwrap();
+        write(root.join("sub/b.py"), "print('b')").unwrap();
+
+        let first = fingerprint_of(&root);
+        assert_eq!(first, fingerprint_of(&root));
+
+        write(root.join("sub/b.py"), "print('c')").unwrap();
+        assert_ne!(first, fingerprint_of(&root));
+
+        tmp_dir.close().unwrap();
+    }
+}
@@ -0,0 +1,35 @@
+use std::result;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static NON_INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(
+        "Input required for \"{0}\" but wg-mod runs in non-interactive mode"
+    )]
+    InputRequired(String),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub fn set_non_interactive(value: bool) {
+    NON_INTERACTIVE.store(value, Ordering::Relaxed);
+}
+
+pub fn is_interactive() -> bool {
+    !NON_INTERACTIVE.load(Ordering::Relaxed)
+}
+
+pub fn ensure_interactive(prompt: &str) -> Result<()> {
+    require_input(is_interactive(), prompt)
+}
+
+// Same as ensure_interactive for a mode given by the caller
+pub fn require_input(interactive: bool, prompt: &str) -> Result<()> {
+    if interactive {
+        Ok(())
+    } else {
+        Err(Error::InputRequired(prompt.to_string()))
+    }
+}
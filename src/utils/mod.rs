@@ -5,7 +5,11 @@ pub mod copy_directory;
 pub mod downloader;
 pub mod extract_archive;
 pub mod file_template;
+pub mod fingerprint;
+pub mod interactive;
 pub mod pattern_validator;
+#[cfg(test)]
+pub mod testing;
 pub mod tmp_dir;
 pub mod zip;
 
@@ -0,0 +1,42 @@
+use crate::config::settings::Settings;
+use crate::config::Configs;
+use crate::sdk::as3::AS3;
+use crate::sdk::asconfigc::ASConfigc;
+use crate::sdk::conda::environment::CondaEnvironment;
+use crate::sdk::game_sources::GameSources;
+use crate::sdk::npm::NPM;
+use crate::utils::zip;
+use std::fs::write;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+// Toolchain pointing into home, for builds that don't run any tool
+pub fn mock_configs(home: &Path) -> Configs {
+    let game_sources_path = home.join("wot-src");
+    git2::Repository::init(&game_sources_path).unwrap();
+
+    Configs {
+        wg_mod_home: home.to_path_buf(),
+        game_sources: GameSources::open(&game_sources_path).unwrap(),
+        game_client: None,
+        conda_environment: CondaEnvironment::from(home.join("conda")),
+        as3: AS3::from(&home.join("as3")),
+        asconfigc: ASConfigc::from(NPM::from(home.join("npm"))),
+        settings: Settings::create_default_settings(home.join("settings.json")),
+    }
+}
+
+// .wotmod holding only a meta.xml
+pub fn mock_mod(archive_path: &Path, id: &str, version: &str) -> PathBuf {
+    let build_dir = tempdir().unwrap();
+    write(
+        build_dir.path().join("meta.xml"),
+        format!("<root><id>{id}</id><version>{version}</version><name>{id}</name><description></description></root>"),
+    )
+    .unwrap();
+
+    let archive_path = archive_path.to_path_buf();
+    zip::archive_directories(&archive_path, &[build_dir.path().to_path_buf()])
+        .unwrap();
+    archive_path
+}
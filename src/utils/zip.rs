@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io;
 use std::io::{Read, Write};
@@ -5,7 +6,7 @@ use std::path::Component;
 use std::path::PathBuf;
 use zip::result::ZipResult;
 use zip::write::SimpleFileOptions;
-use zip::{CompressionMethod, ZipWriter};
+use zip::{CompressionMethod, DateTime, ZipArchive, ZipWriter};
 use zip_extensions::zip_extract;
 
 #[derive(Debug, thiserror::Error)]
@@ -15,6 +16,9 @@ pub enum Error {
 
     #[error("Unable to extract the archive: {0}")]
     ExtractError(String),
+
+    #[error("Unable to read the archive: {0}")]
+    ReadError(String),
 }
 
 fn make_relative_path(root: &PathBuf, current: &PathBuf) -> PathBuf {
@@ -48,50 +52,122 @@ fn path_as_string(path: &std::path::Path) -> String {
     path_str
 }
 
+// 1980-01-01, the oldest date a zip entry can hold
+const DOS_EPOCH: i64 = 315532800;
+
+// Reproducible builds, see https://reproducible-builds.org/specs/source-date-epoch/
+pub fn source_date_epoch() -> i64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<i64>().ok())
+        .unwrap_or(DOS_EPOCH)
+}
+
+fn archive_timestamp() -> DateTime {
+    timestamp_to_date_time(source_date_epoch()).unwrap_or_default()
+}
+
+fn timestamp_to_date_time(timestamp: i64) -> Option<DateTime> {
+    let days = timestamp.div_euclid(86400);
+    let seconds = timestamp.rem_euclid(86400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524
+        - day_of_era / 146096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    // Zip timestamps start in 1980
+    if year < 1980 {
+        return Some(DateTime::default());
+    }
+
+    DateTime::from_date_and_time(
+        u16::try_from(year).ok()?,
+        month as u8,
+        day as u8,
+        (seconds / 3600) as u8,
+        (seconds % 3600 / 60) as u8,
+        (seconds % 60) as u8,
+    )
+    .ok()
+}
+
+fn collect_entries(
+    root: &PathBuf, directory: &PathBuf,
+    entries: &mut BTreeMap<String, Option<PathBuf>>,
+) -> io::Result<()> {
+    for entry in std::fs::read_dir(directory)? {
+        let entry_path = entry?.path();
+        let entry_metadata = std::fs::metadata(&entry_path)?;
+        let relative_path =
+            path_as_string(&make_relative_path(root, &entry_path));
+
+        if entry_metadata.is_file() {
+            if entries.contains_key(&relative_path) {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("Duplicate archive entry {relative_path}"),
+                ));
+            }
+            entries.insert(relative_path, Some(entry_path));
+        } else if entry_metadata.is_dir() {
+            entries.insert(format!("{relative_path}/"), None);
+            collect_entries(root, &entry_path, entries)?;
+        }
+    }
+
+    Ok(())
+}
+
 trait ZipWriterExtensions {
-    fn create_from_directory_with_options(
-        self, directory: &PathBuf,
+    fn create_from_directories_with_options(
+        self, directories: &[PathBuf],
     ) -> ZipResult<()>;
 }
 
 impl<W: Write + io::Seek> ZipWriterExtensions for ZipWriter<W> {
-    fn create_from_directory_with_options(
-        mut self, directory: &PathBuf,
+    // Entries are sorted and stamped with a fixed date and permissions so
+    // the same sources always give the same archive bytes
+    fn create_from_directories_with_options(
+        mut self, directories: &[PathBuf],
     ) -> ZipResult<()> {
-        let file_options = SimpleFileOptions::default()
-            .compression_method(CompressionMethod::Stored);
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Stored)
+            .last_modified_time(archive_timestamp());
+        let file_options = options.unix_permissions(0o644);
+        let directory_options = options.unix_permissions(0o755);
 
-        let mut paths_queue: Vec<PathBuf> = vec![];
-        paths_queue.push(directory.clone());
+        let mut entries: BTreeMap<String, Option<PathBuf>> = BTreeMap::new();
+        for directory in directories {
+            collect_entries(directory, directory, &mut entries)?;
+        }
 
         let mut buffer = Vec::new();
 
-        while let Some(next) = paths_queue.pop() {
-            let directory_entry_iterator = std::fs::read_dir(next)?;
-
-            for entry in directory_entry_iterator {
-                let entry_path = entry?.path();
-                let entry_metadata = std::fs::metadata(entry_path.clone())?;
-                if entry_metadata.is_file() {
-                    let mut f = File::open(&entry_path)?;
-                    f.read_to_end(&mut buffer)?;
-                    let relative_path =
-                        make_relative_path(&directory, &entry_path);
-                    self.start_file(
-                        path_as_string(&relative_path),
-                        file_options,
-                    )?;
+        for (name, path) in entries {
+            match path {
+                | Some(path) => {
+                    File::open(&path)?.read_to_end(&mut buffer)?;
+                    self.start_file(name, file_options)?;
                     self.write_all(buffer.as_ref())?;
                     buffer.clear();
-                } else if entry_metadata.is_dir() {
-                    let relative_path =
-                        make_relative_path(&directory, &entry_path);
-                    self.add_directory(
-                        path_as_string(&relative_path),
-                        file_options,
-                    )?;
-                    paths_queue.push(entry_path.clone());
-                }
+                },
+                | None => {
+                    self.add_directory(name, directory_options)?;
+                },
             }
         }
 
@@ -100,15 +176,15 @@ impl<W: Write + io::Seek> ZipWriterExtensions for ZipWriter<W> {
     }
 }
 
-pub fn archive_directory(
-    archive_file: &PathBuf, directory: &PathBuf,
+pub fn archive_directories(
+    archive_file: &PathBuf, directories: &[PathBuf],
 ) -> Result<(), Error> {
     let file = File::create(archive_file)
         .map_err(|error| Error::ArchiveError(error.to_string()))?;
     let zip_writer = ZipWriter::new(file);
 
     zip_writer
-        .create_from_directory_with_options(directory)
+        .create_from_directories_with_options(directories)
         .map_err(|error| Error::ArchiveError(error.to_string()))
 }
 
@@ -118,3 +194,82 @@ pub fn extract(
     zip_extract(archive_file, directory)
         .map_err(|error| Error::ExtractError(error.to_string()))
 }
+
+pub fn read_entry(
+    archive_file: &PathBuf, entry_name: &str,
+) -> Result<Vec<u8>, Error> {
+    let file = File::open(archive_file)
+        .map_err(|error| Error::ReadError(error.to_string()))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|error| Error::ReadError(error.to_string()))?;
+    let mut entry = archive
+        .by_name(entry_name)
+        .map_err(|error| Error::ReadError(error.to_string()))?;
+
+    let mut content = vec![];
+    entry
+        .read_to_end(&mut content)
+        .map_err(|error| Error::ReadError(error.to_string()))?;
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, read, write};
+    use tempfile::tempdir;
+
+    #[test]
+    fn archive_is_byte_identical_across_runs() {
+        let tmp_dir = tempdir().unwrap();
+        let first_root = tmp_dir.path().join("build");
+        let second_root = tmp_dir.path().join("resources");
+        create_dir_all(first_root.join("res/scripts")).unwrap();
+        create_dir_all(second_root.join("res/gui")).unwrap();
+        write(first_root.join("meta.xml"), "<root></root>").unwrap();
+        write(first_root.join("res/scripts/mod_test.pyc"), [0x03, 0xf3])
+            .unwrap();
+        write(second_root.join("res/gui/settings.xml"), "<settings/>").unwrap();
+
+        let roots = [first_root.clone(), second_root];
+        let first_archive = tmp_dir.path().join("first.wotmod");
+        archive_directories(&first_archive, &roots).unwrap();
+
+        // Touch a file so its modification time changes between builds
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        write(first_root.join("meta.xml"), "<root></root>").unwrap();
+
+        let second_archive = tmp_dir.path().join("second.wotmod");
+        archive_directories(&second_archive, &roots).unwrap();
+
+        assert_eq!(
+            read(&first_archive).unwrap(),
+            read(&second_archive).unwrap()
+        );
+
+        let archive =
+            ZipArchive::new(File::open(&first_archive).unwrap()).unwrap();
+        let names = archive.file_names().collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "meta.xml",
+                "res/",
+                "res/gui/",
+                "res/gui/settings.xml",
+                "res/scripts/",
+                "res/scripts/mod_test.pyc"
+            ]
+        );
+    }
+
+    #[test]
+    fn source_date_epoch_to_zip_date() {
+        let date = timestamp_to_date_time(1700000000).unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2023, 11, 14));
+        assert_eq!((date.hour(), date.minute()), (22, 13));
+
+        assert_eq!(timestamp_to_date_time(0).unwrap(), DateTime::default());
+    }
+}
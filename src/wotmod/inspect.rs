@@ -0,0 +1,192 @@
+use crate::config::mod_conf::ModConf;
+use crate::utils::zip;
+use crate::wotmod::{compression_name, read_entries, Error};
+use serde_derive::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::PathBuf;
+use std::result;
+
+type Result<T> = result::Result<T, Error>;
+
+#[derive(Debug, Serialize)]
+pub struct FileSummary {
+    pub name: String,
+    pub size: u64,
+    pub compressed_size: u64,
+    pub compression: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Inspection {
+    pub meta: Option<ModConf>,
+    pub files: Vec<FileSummary>,
+    pub kinds: BTreeMap<String, usize>,
+    pub compression: BTreeMap<String, usize>,
+    pub total_size: u64,
+}
+
+fn file_kind(name: &str) -> String {
+    let file_name = name.rsplit('/').next().unwrap_or(name);
+
+    match file_name.rsplit_once('.') {
+        | Some((stem, extension)) if !stem.is_empty() => {
+            extension.to_lowercase()
+        },
+        | _ => "other".to_string(),
+    }
+}
+
+pub fn inspect(archive_path: &PathBuf) -> Result<Inspection> {
+    let entries = read_entries(archive_path)?;
+
+    let meta =
+        zip::read_entry(archive_path, "meta.xml")
+            .ok()
+            .and_then(|content| {
+                ModConf::from_meta(&String::from_utf8_lossy(&content)).ok()
+            });
+
+    let mut files = vec![];
+    let mut kinds = BTreeMap::new();
+    let mut compression = BTreeMap::new();
+
+    for entry in entries.iter().filter(|entry| !entry.is_dir()) {
+        let method = compression_name(entry.compression);
+
+        *kinds.entry(file_kind(&entry.name)).or_insert(0) += 1;
+        *compression.entry(method.clone()).or_insert(0) += 1;
+        files.push(FileSummary {
+            name: entry.name.clone(),
+            size: entry.size,
+            compressed_size: entry.compressed_size,
+            compression: method,
+        });
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(Inspection {
+        meta,
+        total_size: files.iter().map(|file| file.size).sum(),
+        files,
+        kinds,
+        compression,
+    })
+}
+
+fn join_counts(counts: &BTreeMap<String, usize>) -> String {
+    counts
+        .iter()
+        .map(|(name, count)| format!("{name}: {count}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.meta {
+            | Some(meta) => {
+                writeln!(f, "Id:          {}", meta.package_name)?;
+                writeln!(f, "Version:     {}", meta.version)?;
+                writeln!(f, "Name:        {}", meta.name)?;
+                writeln!(f, "Description: {}", meta.description)?;
+            },
+            | None => writeln!(f, "No readable meta.xml")?,
+        }
+
+        writeln!(f)?;
+        let mut printed_directories = BTreeSet::new();
+        for file in &self.files {
+            let components = file.name.split('/').collect::<Vec<_>>();
+            let depth = components.len() - 1;
+
+            for index in 0..depth {
+                let directory = components[..=index].join("/");
+                if printed_directories.insert(directory) {
+                    writeln!(
+                        f,
+                        "{}{}/",
+                        "  ".repeat(index),
+                        components[index]
+                    )?;
+                }
+            }
+            writeln!(
+                f,
+                "{}{} ({} B, {})",
+                "  ".repeat(depth),
+                components[depth],
+                file.size,
+                file.compression
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Kinds:       {}", join_counts(&self.kinds))?;
+        writeln!(f, "Compression: {}", join_counts(&self.compression))?;
+        write!(
+            f,
+            "Total:       {} file(s), {} B",
+            self.files.len(),
+            self.total_size
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, write};
+    use tempfile::tempdir;
+
+    #[test]
+    fn inspect_archive() {
+        let tmp_dir = tempdir().unwrap();
+        let build_path = tmp_dir.path().join("build");
+        create_dir_all(build_path.join("res/scripts/client/gui/mods")).unwrap();
+        create_dir_all(build_path.join("res/gui/flash")).unwrap();
+        write(
+            build_path.join("meta.xml"),
+            "<root><id>com.example.mod</id><version>1.0.0</version><name>Mod</name><description>Test</description></root>",
+        )
+        .unwrap();
+        write(
+            build_path.join("res/scripts/client/gui/mods/mod_test.pyc"),
+            [0; 8],
+        )
+        .unwrap();
+        write(build_path.join("res/gui/flash/mod.swf"), [0; 16]).unwrap();
+
+        let archive_path = tmp_dir.path().join("mod.wotmod");
+        zip::archive_directories(&archive_path, &[build_path]).unwrap();
+
+        let inspection = inspect(&archive_path).unwrap();
+        assert_eq!(
+            inspection.to_string(),
+            "Id:          com.example.mod
+Version:     1.0.0
+Name:        Mod
+Description: Test
+
+meta.xml (108 B, stored)
+res/
+  gui/
+    flash/
+      mod.swf (16 B, stored)
+  scripts/
+    client/
+      gui/
+        mods/
+          mod_test.pyc (8 B, stored)
+
+Kinds:       pyc: 1, swf: 1, xml: 1
+Compression: stored: 3
+Total:       3 file(s), 132 B"
+        );
+
+        let json = serde_json::to_value(&inspection).unwrap();
+        assert_eq!(json["meta"]["id"], "com.example.mod");
+        assert_eq!(json["kinds"]["swf"], 1);
+        assert_eq!(json["files"][1]["name"], "res/gui/flash/mod.swf");
+    }
+}
@@ -0,0 +1,120 @@
+pub mod inspect;
+pub mod pack;
+pub mod verify;
+
+use std::fs::read;
+use std::path::PathBuf;
+use std::{io, result};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Unable to read {0:?}\n{1}")]
+    ReadError(PathBuf, io::Error),
+
+    #[error("{0:?} is not a valid zip archive: {1}")]
+    FormatError(PathBuf, String),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+const CENTRAL_DIRECTORY_HEADER_SIGNATURE: u32 = 0x02014b50;
+
+pub const STORED: u16 = 0;
+
+// One record of the zip central directory, read as-is: the zip crate
+// merges duplicate names, which the game loader does not tolerate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub name: String,
+    pub compression: u16,
+    pub compressed_size: u64,
+    pub size: u64,
+}
+
+impl Entry {
+    pub fn is_dir(&self) -> bool {
+        self.name.ends_with('/')
+    }
+}
+
+pub fn compression_name(method: u16) -> String {
+    match method {
+        | STORED => "stored".to_string(),
+        | 8 => "deflated".to_string(),
+        | 9 => "deflate64".to_string(),
+        | 12 => "bzip2".to_string(),
+        | 14 => "lzma".to_string(),
+        | 93 => "zstd".to_string(),
+        | 95 => "xz".to_string(),
+        | method => format!("method {method}"),
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let value = bytes.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([value[0], value[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let value = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([value[0], value[1], value[2], value[3]]))
+}
+
+fn parse_entries(bytes: &[u8]) -> result::Result<Vec<Entry>, String> {
+    let end_of_directory = (0..bytes.len().saturating_sub(21))
+        .rev()
+        .find(|offset| {
+            read_u32(bytes, *offset) == Some(END_OF_CENTRAL_DIRECTORY_SIGNATURE)
+        })
+        .ok_or("end of central directory not found")?;
+
+    let count = read_u16(bytes, end_of_directory + 10)
+        .ok_or("truncated end of central directory")?;
+    let mut offset = read_u32(bytes, end_of_directory + 16)
+        .ok_or("truncated end of central directory")?
+        as usize;
+
+    let mut entries = vec![];
+    for _ in 0..count {
+        if read_u32(bytes, offset) != Some(CENTRAL_DIRECTORY_HEADER_SIGNATURE) {
+            return Err(format!(
+                "invalid central directory header at {offset}"
+            ));
+        }
+
+        let field = |position: usize| {
+            read_u16(bytes, offset + position)
+                .ok_or("truncated central directory header")
+        };
+        let compression = field(10)?;
+        let name_length = field(28)? as usize;
+        let extra_length = field(30)? as usize;
+        let comment_length = field(32)? as usize;
+        let compressed_size = read_u32(bytes, offset + 20)
+            .ok_or("truncated central directory header")?;
+        let size = read_u32(bytes, offset + 24)
+            .ok_or("truncated central directory header")?;
+        let name = bytes
+            .get(offset + 46..offset + 46 + name_length)
+            .ok_or("truncated entry name")?;
+
+        entries.push(Entry {
+            name: String::from_utf8_lossy(name).to_string(),
+            compression,
+            compressed_size: compressed_size as u64,
+            size: size as u64,
+        });
+        offset += 46 + name_length + extra_length + comment_length;
+    }
+
+    Ok(entries)
+}
+
+pub fn read_entries(archive_path: &PathBuf) -> Result<Vec<Entry>> {
+    let bytes = read(archive_path)
+        .map_err(|e| Error::ReadError(archive_path.clone(), e))?;
+
+    parse_entries(&bytes)
+        .map_err(|e| Error::FormatError(archive_path.clone(), e))
+}
@@ -0,0 +1,292 @@
+use crate::config::mod_conf;
+use crate::config::mod_conf::ModConf;
+use crate::sdk::game_client;
+use crate::sdk::game_client::read_archive_meta;
+use crate::utils::file_template::render_template;
+use crate::utils::fingerprint::sha256_file;
+use crate::utils::{file_template, fingerprint, zip};
+use serde_derive::Serialize;
+use serde_json::json;
+use std::fs::{copy, create_dir_all, write};
+use std::path::PathBuf;
+use std::{io, result};
+use tempfile::tempdir;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("No .wotmod to pack")]
+    EmptyPackError,
+
+    #[error("Unable to read {0:?}\n{1}")]
+    ModMetaError(PathBuf, game_client::Error),
+
+    #[error("Invalid meta.xml in {0:?}\n{1}")]
+    InvalidModMetaError(PathBuf, mod_conf::Error),
+
+    #[error("The mod {0} is packed twice")]
+    DuplicateModError(String),
+
+    #[error("Unable to hash the archive\n{0}")]
+    HashError(#[from] fingerprint::Error),
+
+    #[error("Unable to render the pack readme\n{0}")]
+    TemplateError(#[from] file_template::Error),
+
+    #[error("Unable to serialize the pack manifest\n{0}")]
+    SerializeError(#[from] serde_json::Error),
+
+    #[error("Unable to write the pack\n{0}")]
+    WriteError(#[from] io::Error),
+
+    #[error("Unable to write the pack archive\n{0}")]
+    ArchiveError(#[from] zip::Error),
+}
+
+type Result<T> = result::Result<T, Error>;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+const README_TEMPLATE: &str = "{{name}}
+========================================
+
+Mods for World of Tanks {{game_version}}.
+
+Installation
+------------
+Extract this archive into your World of Tanks folder, the .wotmod files
+end up in mods/{{game_version}}/.
+
+Content
+-------
+{{#each mods}}
+- {{name}} {{version}} ({{id}})
+{{#if description}}
+  {{description}}
+{{/if}}
+{{/each}}
+";
+
+pub struct PackedMod {
+    pub archive: PathBuf,
+    // License files bundled under licenses/<id>/
+    pub licenses: Vec<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestEntry {
+    pub id: String,
+    pub version: String,
+    pub name: String,
+    pub file: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    pub name: String,
+    pub game_version: String,
+    pub mods: Vec<ManifestEntry>,
+}
+
+pub struct PackOptions {
+    pub name: String,
+    pub game_version: String,
+    pub licenses: Vec<PathBuf>,
+}
+
+fn file_name(path: &PathBuf) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+pub fn pack(
+    mods: &[PackedMod], options: &PackOptions, output: &PathBuf,
+) -> Result<Manifest> {
+    if mods.is_empty() {
+        return Err(Error::EmptyPackError);
+    }
+
+    let tmp_dir = tempdir()?;
+    let root = tmp_dir.path().to_path_buf();
+    let mods_path = root.join("mods").join(&options.game_version);
+    create_dir_all(&mods_path)?;
+
+    let mut metas: Vec<ModConf> = vec![];
+    let mut manifest = Manifest {
+        name: options.name.clone(),
+        game_version: options.game_version.clone(),
+        mods: vec![],
+    };
+
+    for packed_mod in mods {
+        let meta = read_archive_meta(&packed_mod.archive)
+            .map_err(|e| Error::ModMetaError(packed_mod.archive.clone(), e))?;
+        meta.validate_file_name_fields().map_err(|e| {
+            Error::InvalidModMetaError(packed_mod.archive.clone(), e)
+        })?;
+        if metas
+            .iter()
+            .any(|other| other.package_name == meta.package_name)
+        {
+            return Err(Error::DuplicateModError(meta.package_name));
+        }
+
+        let file = format!("{}_{}.wotmod", meta.package_name, meta.version);
+        copy(&packed_mod.archive, mods_path.join(&file))?;
+
+        for license in &packed_mod.licenses {
+            let licenses_path = root.join("licenses").join(&meta.package_name);
+            create_dir_all(&licenses_path)?;
+            copy(license, licenses_path.join(file_name(license)))?;
+        }
+
+        manifest.mods.push(ManifestEntry {
+            id: meta.package_name.clone(),
+            version: meta.version.clone(),
+            name: meta.name.clone(),
+            file: format!("mods/{}/{}", options.game_version, file),
+            sha256: sha256_file(&packed_mod.archive)?,
+        });
+        metas.push(meta);
+    }
+
+    for license in &options.licenses {
+        copy(license, root.join(file_name(license)))?;
+    }
+
+    let readme = render_template(
+        README_TEMPLATE,
+        &json!({
+            "name": options.name,
+            "game_version": options.game_version,
+            "mods": metas.iter().map(|meta| json!({
+                "id": meta.package_name,
+                "version": meta.version,
+                "name": meta.name,
+                "description": meta.description,
+            })).collect::<Vec<_>>(),
+        }),
+    )?;
+    write(root.join("README.txt"), readme)?;
+    write(
+        root.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+
+    if let Some(parent) = output.parent() {
+        create_dir_all(parent)?;
+    }
+    zip::archive_directories(output, &[root])?;
+    tmp_dir.close()?;
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::mock_mod;
+    use ::zip::ZipArchive;
+    use sha2::{Digest, Sha256};
+    use std::fs::read;
+
+    #[test]
+    fn pack_mods() {
+        let tmp_dir = tempdir().unwrap();
+        let tmp_path = tmp_dir.path().to_path_buf();
+        let first = mock_mod(
+            &tmp_path.join("com.example.first.wotmod"),
+            "com.example.first",
+            "1.0.0",
+        );
+        let second = mock_mod(
+            &tmp_path.join("com.example.second.wotmod"),
+            "com.example.second",
+            "2.1.0",
+        );
+        let license = tmp_path.join("LICENSE");
+        write(&license, "MIT").unwrap();
+
+        let output = tmp_path.join("dist/pack.zip");
+        let manifest = pack(
+            &[
+                PackedMod {
+                    archive: first.clone(),
+                    licenses: vec![license.clone()],
+                },
+                PackedMod {
+                    archive: second,
+                    licenses: vec![],
+                },
+            ],
+            &PackOptions {
+                name: "Example pack".to_string(),
+                game_version: "1.26.0.1".to_string(),
+                licenses: vec![license],
+            },
+            &output,
+        )
+        .unwrap();
+
+        let expected_hash =
+            format!("{:x}", Sha256::digest(read(&first).unwrap()));
+        assert_eq!(manifest.mods[0].sha256, expected_hash);
+        assert_eq!(
+            manifest.mods[1].file,
+            "mods/1.26.0.1/com.example.second_2.1.0.wotmod"
+        );
+
+        let archive =
+            ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+        let mut names = archive.file_names().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "LICENSE",
+                "README.txt",
+                "licenses/",
+                "licenses/com.example.first/",
+                "licenses/com.example.first/LICENSE",
+                "manifest.json",
+                "mods/",
+                "mods/1.26.0.1/",
+                "mods/1.26.0.1/com.example.first_1.0.0.wotmod",
+                "mods/1.26.0.1/com.example.second_2.1.0.wotmod",
+            ]
+        );
+
+        let readme = zip::read_entry(&output, "README.txt").unwrap();
+        assert!(String::from_utf8(readme)
+            .unwrap()
+            .contains("- com.example.second 2.1.0 (com.example.second)"));
+    }
+
+    #[test]
+    fn refuse_mod_with_a_path_as_version() {
+        let tmp_dir = tempdir().unwrap();
+        let tmp_path = tmp_dir.path().to_path_buf();
+        let archive = mock_mod(
+            &tmp_path.join("com.example.mod.wotmod"),
+            "com.example.mod",
+            "../../evil",
+        );
+
+        let result = pack(
+            &[PackedMod {
+                archive,
+                licenses: vec![],
+            }],
+            &PackOptions {
+                name: "Example pack".to_string(),
+                game_version: "1.26.0.1".to_string(),
+                licenses: vec![],
+            },
+            &tmp_path.join("pack.zip"),
+        );
+
+        assert!(matches!(result, Err(Error::InvalidModMetaError(..))));
+        assert!(!tmp_path.join("pack.zip").exists());
+    }
+}
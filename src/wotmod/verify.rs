@@ -0,0 +1,293 @@
+use crate::builder::build_info;
+use crate::config::mod_conf::{ModConf, PACKAGE_NAME_PATTERN, VERSION_PATTERN};
+use crate::utils::zip;
+use crate::wotmod::{read_entries, Entry, Error, STORED};
+use regex::Regex;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::result;
+
+type Result<T> = result::Result<T, Error>;
+
+const META_FILE: &str = "meta.xml";
+const PYTHON_MODS_PATH: &str = "res/scripts/client/gui/mods/";
+
+#[derive(Debug, Default, Clone)]
+pub struct VerifyOptions {
+    pub allow_sources: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub entry: Option<String>,
+    pub message: String,
+}
+
+impl Issue {
+    fn archive(message: &str) -> Self {
+        Self {
+            entry: None,
+            message: message.to_string(),
+        }
+    }
+
+    fn entry(entry: &Entry, message: &str) -> Self {
+        Self {
+            entry: Some(entry.name.clone()),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.entry {
+            | Some(entry) => write!(f, "{entry}: {}", self.message),
+            | None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+fn is_absolute(name: &str) -> bool {
+    name.starts_with('/')
+        || name.starts_with('\\')
+        || name.chars().nth(1) == Some(':')
+        || name.split(['/', '\\']).any(|component| component == "..")
+}
+
+// Bytecode directly inside gui/mods, where the game looks for entry points
+fn top_level_python_file(name: &str) -> Option<&str> {
+    name.strip_prefix(PYTHON_MODS_PATH)
+        .filter(|file| !file.contains('/') && file.ends_with(".pyc"))
+}
+
+// Files directly inside gui/mods named mod_*.pyc are loaded by the game
+fn is_python_entry(name: &str) -> bool {
+    top_level_python_file(name).is_some_and(|file| file.starts_with("mod_"))
+}
+
+// Other top level files are never loaded, except the module wg-mod generates
+fn is_misplaced_python_file(name: &str) -> bool {
+    top_level_python_file(name).is_some_and(|file| {
+        !file.starts_with("mod_")
+            && !file.starts_with(build_info::MODULE_PREFIX)
+    })
+}
+
+fn check_entries(entries: &[Entry], options: &VerifyOptions) -> Vec<Issue> {
+    let mut issues = vec![];
+    let mut names = HashSet::new();
+
+    for entry in entries {
+        if !names.insert(entry.name.as_str()) {
+            issues.push(Issue::entry(entry, "duplicate entry"));
+        }
+        if entry.compression != STORED {
+            issues.push(Issue::entry(
+                entry,
+                &format!(
+                    "compressed with method {}, the game only reads stored entries",
+                    entry.compression
+                ),
+            ));
+        }
+        if is_absolute(&entry.name) {
+            issues.push(Issue::entry(entry, "absolute or parent path"));
+        } else if entry.name != META_FILE && !entry.name.starts_with("res/") {
+            issues.push(Issue::entry(entry, "outside of res/"));
+        }
+        if is_misplaced_python_file(&entry.name) {
+            issues.push(Issue::entry(
+                entry,
+                "not a mod_*.pyc entry point, move helper modules into a package",
+            ));
+        }
+        if entry.name.ends_with(".py") && !options.allow_sources {
+            issues.push(Issue::entry(
+                entry,
+                "Python source shipped, pass --allow-sources if intended",
+            ));
+        }
+    }
+
+    let has_python = entries.iter().any(|entry| entry.name.ends_with(".pyc"));
+    let has_entry = entries.iter().any(|entry| is_python_entry(&entry.name));
+    if has_python && !has_entry {
+        issues.push(Issue::archive(&format!(
+            "no Python entry point, the game only loads {PYTHON_MODS_PATH}mod_*.pyc"
+        )));
+    }
+
+    issues
+}
+
+fn check_meta(content: &[u8]) -> Vec<Issue> {
+    let mut issues = vec![];
+
+    let content = String::from_utf8_lossy(content);
+    let mod_conf = match ModConf::from_meta(&content) {
+        | Ok(mod_conf) => mod_conf,
+        | Err(e) => {
+            return vec![Issue::archive(&format!("invalid {META_FILE}: {e}"))]
+        },
+    };
+
+    let package_name = Regex::new(PACKAGE_NAME_PATTERN).unwrap();
+    if !package_name.is_match(&mod_conf.package_name) {
+        issues.push(Issue::archive(&format!(
+            "invalid id \"{}\" in {META_FILE}",
+            mod_conf.package_name
+        )));
+    }
+
+    let version = Regex::new(VERSION_PATTERN).unwrap();
+    if !version.is_match(&mod_conf.version) {
+        issues.push(Issue::archive(&format!(
+            "invalid version \"{}\" in {META_FILE}",
+            mod_conf.version
+        )));
+    }
+
+    issues
+}
+
+pub fn verify(
+    archive_path: &PathBuf, options: &VerifyOptions,
+) -> Result<Vec<Issue>> {
+    let entries = read_entries(archive_path)?;
+    let mut issues = check_entries(&entries, options);
+
+    if entries.iter().any(|entry| entry.name == META_FILE) {
+        match zip::read_entry(archive_path, META_FILE) {
+            | Ok(content) => issues.extend(check_meta(&content)),
+            | Err(e) => issues.push(Issue::archive(&format!(
+                "unable to read {META_FILE}: {e}"
+            ))),
+        }
+    } else {
+        issues.push(Issue::archive(&format!("missing {META_FILE}")));
+    }
+
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::zip::write::SimpleFileOptions;
+    use ::zip::{CompressionMethod, ZipWriter};
+    use std::fs::{create_dir_all, write, File};
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    const META: &str = "<root><id>com.example.mod</id><version>1.0.0</version><name>Mod</name><description></description></root>";
+
+    #[test]
+    fn verify_valid_archive() {
+        let tmp_dir = tempdir().unwrap();
+        let build_path = tmp_dir.path().join("build");
+        let mods_path = build_path.join(PYTHON_MODS_PATH);
+        create_dir_all(&mods_path).unwrap();
+        write(build_path.join(META_FILE), META).unwrap();
+        write(mods_path.join("mod_test.pyc"), [0x03, 0xf3, 0x0d, 0x0a])
+            .unwrap();
+
+        let archive_path = tmp_dir.path().join("mod.wotmod");
+        zip::archive_directories(&archive_path, &[build_path]).unwrap();
+
+        let issues = verify(&archive_path, &VerifyOptions::default()).unwrap();
+        assert_eq!(issues, vec![]);
+    }
+
+    #[test]
+    fn verify_invalid_archive() {
+        let tmp_dir = tempdir().unwrap();
+        let archive_path = tmp_dir.path().join("mod.wotmod");
+
+        let mut writer = ZipWriter::new(File::create(&archive_path).unwrap());
+        let stored = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Stored);
+        let deflated = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated);
+        writer.start_file(META_FILE, stored).unwrap();
+        writer
+            .write_all(META.replace("1.0.0", "1.0").as_bytes())
+            .unwrap();
+        writer
+            .start_file("res/scripts/client/gui/mods/helper.pyc", deflated)
+            .unwrap();
+        writer.write_all(b"pyc").unwrap();
+        writer
+            .start_file("res/scripts/client/gui/mods/mod_test.py", stored)
+            .unwrap();
+        writer.start_file("scripts/mod_test.pyc", stored).unwrap();
+        writer.start_file("/res/absolute.xml", stored).unwrap();
+        writer.finish().unwrap();
+
+        let issues = verify(&archive_path, &VerifyOptions::default())
+            .unwrap()
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            issues,
+            vec![
+                "res/scripts/client/gui/mods/helper.pyc: compressed with method 8, the game only reads stored entries",
+                "res/scripts/client/gui/mods/helper.pyc: not a mod_*.pyc entry point, move helper modules into a package",
+                "res/scripts/client/gui/mods/mod_test.py: Python source shipped, pass --allow-sources if intended",
+                "scripts/mod_test.pyc: outside of res/",
+                "/res/absolute.xml: absolute or parent path",
+                "no Python entry point, the game only loads res/scripts/client/gui/mods/mod_*.pyc",
+                "invalid version \"1.0\" in meta.xml",
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_top_level_helper_modules() {
+        let entries = [
+            "mod_test.pyc",
+            "helper.pyc",
+            "_build_info_com_example_mod.pyc",
+            "test/__init__.pyc",
+            "test/helper.pyc",
+        ]
+        .map(|file| Entry {
+            name: format!("{PYTHON_MODS_PATH}{file}"),
+            compression: STORED,
+            compressed_size: 0,
+            size: 0,
+        });
+
+        let issues = check_entries(&entries, &VerifyOptions::default());
+        assert_eq!(
+            issues,
+            vec![Issue {
+                entry: Some(format!("{PYTHON_MODS_PATH}helper.pyc")),
+                message: "not a mod_*.pyc entry point, move helper modules into a package".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn detect_duplicate_entries() {
+        let entry = Entry {
+            name: "res/gui/flash/mod.swf".to_string(),
+            compression: STORED,
+            compressed_size: 0,
+            size: 0,
+        };
+
+        let issues =
+            check_entries(&[entry.clone(), entry], &VerifyOptions::default());
+        assert_eq!(
+            issues,
+            vec![Issue {
+                entry: Some("res/gui/flash/mod.swf".to_string()),
+                message: "duplicate entry".to_string(),
+            }]
+        );
+    }
+}